@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use crate::{Accuracy, GeoError, GeoLocation, Geolocatable, PostalData};
+
+const MAGIC: &[u8; 8] = b"GEOPOST1";
+const HEADER_SIZE: usize = 24;
+const STRING_FIELDS: usize = 9;
+const STRING_REF_SIZE: usize = 12;
+const RECORD_SIZE: usize = 128;
+// marks an absent optional string
+const NO_STRING: u32 = u32::MAX;
+// marks a record without an accuracy code
+const NO_ACCURACY: u8 = u8::MAX;
+
+fn invalid_data(message: &str) -> GeoError {
+    GeoError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    ))
+}
+
+/// Write `PostalData` records in a compact binary format that can be memory-mapped.
+///
+/// The file holds a header, one fixed-width record per postal code and an arena of
+/// interned strings, so repeated admin names are only stored once. Open it with
+/// `PostalBinary::open`.
+///
+/// # Arguments
+///
+/// * `records` - A slice of `PostalData` structs.
+/// * `writer` - The `Write` implementation to write to.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the records were written.
+pub fn write_postal_binary<W: Write>(
+    records: &[PostalData],
+    mut writer: W,
+) -> Result<(), GeoError> {
+    let mut arena: Vec<u8> = Vec::new();
+    let mut interned: HashMap<&str, (u64, u32)> = HashMap::new();
+    let mut body = Vec::with_capacity(records.len() * RECORD_SIZE);
+
+    for record in records {
+        let fields = [
+            Some(&record.country_code),
+            Some(&record.postal_code),
+            record.place_name.as_ref(),
+            record.admin_name1.as_ref(),
+            record.admin_code1.as_ref(),
+            record.admin_name2.as_ref(),
+            record.admin_code2.as_ref(),
+            record.admin_name3.as_ref(),
+            record.admin_code3.as_ref(),
+        ];
+
+        let start = body.len();
+        for field in fields {
+            let (offset, len) = match field {
+                Some(value) => *interned.entry(value.as_str()).or_insert_with(|| {
+                    let offset = arena.len() as u64;
+                    arena.extend_from_slice(value.as_bytes());
+                    (offset, value.len() as u32)
+                }),
+                None => (0, NO_STRING),
+            };
+            body.extend_from_slice(&offset.to_le_bytes());
+            body.extend_from_slice(&len.to_le_bytes());
+        }
+
+        let (latitude, longitude) = match &record.geolocation {
+            Some(location) => (location.latitude, location.longitude),
+            None => (f64::NAN, f64::NAN),
+        };
+        body.extend_from_slice(&latitude.to_le_bytes());
+        body.extend_from_slice(&longitude.to_le_bytes());
+        body.push(record.accuracy.code().unwrap_or(NO_ACCURACY));
+        body.push(u8::from(record.geolocation.is_some()));
+        body.resize(start + RECORD_SIZE, 0);
+    }
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&(records.len() as u64).to_le_bytes())?;
+    writer.write_all(&(arena.len() as u64).to_le_bytes())?;
+    writer.write_all(&body)?;
+    writer.write_all(&arena)?;
+    writer.flush()?;
+
+    log::debug!(
+        "Wrote {} records with {} bytes of strings",
+        records.len(),
+        arena.len()
+    );
+
+    Ok(())
+}
+
+/// A memory-mapped file of postal records written by `write_postal_binary`.
+///
+/// Opening the file only maps it into memory, so it starts almost instantly and the pages
+/// are shared between processes mapping the same file. Records are exposed as borrowed
+/// `PostalRecordView`s.
+#[derive(Debug)]
+pub struct PostalBinary {
+    mmap: memmap2::Mmap,
+    len: usize,
+}
+
+impl PostalBinary {
+    /// Open a binary postal file.
+    ///
+    /// The header and every string reference are validated, so accessing the records
+    /// afterwards cannot fail. The file must not be modified while it is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of a file written by `write_postal_binary`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `PostalBinary`, or an `InvalidData` I/O error if the file
+    /// is not a valid binary postal file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GeoError> {
+        let file = File::open(path)?;
+        // SAFETY: the map is read-only, and callers must not modify the file while it is
+        // open, which is the usual contract for memory-mapped files
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+        if mmap.len() < HEADER_SIZE || &mmap[..8] != MAGIC {
+            return Err(invalid_data("not a geo_rust binary postal file"));
+        }
+        let len = u64::from_le_bytes(mmap[8..16].try_into().unwrap()) as usize;
+        let arena_len = u64::from_le_bytes(mmap[16..24].try_into().unwrap()) as usize;
+        let expected = len
+            .checked_mul(RECORD_SIZE)
+            .and_then(|size| size.checked_add(HEADER_SIZE)?.checked_add(arena_len));
+        if expected != Some(mmap.len()) {
+            return Err(invalid_data("binary postal file is truncated"));
+        }
+
+        let binary = PostalBinary { mmap, len };
+        let arena = std::str::from_utf8(binary.arena())
+            .map_err(|_| invalid_data("binary postal file contains invalid UTF-8"))?;
+        for index in 0..len {
+            for field in 0..STRING_FIELDS {
+                if let Some((start, end)) = binary.string_range(index, field) {
+                    if end > arena.len()
+                        || !arena.is_char_boundary(start)
+                        || !arena.is_char_boundary(end)
+                    {
+                        return Err(invalid_data("binary postal file has an invalid string"));
+                    }
+                }
+            }
+        }
+
+        log::debug!("Mapped {} postal records", len);
+
+        Ok(binary)
+    }
+
+    /// Get the number of records.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get a record by index.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The 0-based index of the record, in the order it was written.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a `PostalRecordView`, or `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<PostalRecordView<'_>> {
+        if index >= self.len {
+            return None;
+        }
+
+        let record = self.record(index);
+        let field = |field| {
+            let (start, end) = self.string_range(index, field)?;
+            // the ranges were checked to be on character boundaries of the UTF-8 arena in `open`
+            Some(std::str::from_utf8(&self.arena()[start..end]).unwrap_or_default())
+        };
+        let float =
+            |offset: usize| f64::from_le_bytes(record[offset..offset + 8].try_into().unwrap());
+        let coordinates = STRING_FIELDS * STRING_REF_SIZE;
+
+        Some(PostalRecordView {
+            country_code: field(0).unwrap_or_default(),
+            postal_code: field(1).unwrap_or_default(),
+            place_name: field(2),
+            admin_name1: field(3),
+            admin_code1: field(4),
+            admin_name2: field(5),
+            admin_code2: field(6),
+            admin_name3: field(7),
+            admin_code3: field(8),
+            geolocation: (record[coordinates + 17] == 1).then(|| GeoLocation {
+                latitude: float(coordinates),
+                longitude: float(coordinates + 8),
+            }),
+            accuracy: match record[coordinates + 16] {
+                NO_ACCURACY => Accuracy::NoAccuracyData,
+                code => code.to_string().parse().unwrap_or(Accuracy::NoAccuracyData),
+            },
+        })
+    }
+
+    /// Iterate over the records in the order they were written.
+    pub fn iter(&self) -> impl Iterator<Item = PostalRecordView<'_>> {
+        (0..self.len).filter_map(|index| self.get(index))
+    }
+
+    fn record(&self, index: usize) -> &[u8] {
+        let start = HEADER_SIZE + index * RECORD_SIZE;
+        &self.mmap[start..start + RECORD_SIZE]
+    }
+
+    fn arena(&self) -> &[u8] {
+        &self.mmap[HEADER_SIZE + self.len * RECORD_SIZE..]
+    }
+
+    fn string_range(&self, index: usize, field: usize) -> Option<(usize, usize)> {
+        let offset = field * STRING_REF_SIZE;
+        let reference = &self.record(index)[offset..offset + STRING_REF_SIZE];
+        let start = u64::from_le_bytes(reference[..8].try_into().unwrap()) as usize;
+        let len = u32::from_le_bytes(reference[8..].try_into().unwrap());
+        if len == NO_STRING {
+            return None;
+        }
+
+        Some((start, start.saturating_add(len as usize)))
+    }
+}
+
+/// A postal record borrowed from a `PostalBinary`.
+///
+/// The fields mirror `PostalData`, but the strings borrow from the memory-mapped file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostalRecordView<'a> {
+    pub country_code: &'a str,
+    pub postal_code: &'a str,
+    pub place_name: Option<&'a str>,
+    pub admin_name1: Option<&'a str>,
+    pub admin_code1: Option<&'a str>,
+    pub admin_name2: Option<&'a str>,
+    pub admin_code2: Option<&'a str>,
+    pub admin_name3: Option<&'a str>,
+    pub admin_code3: Option<&'a str>,
+    pub geolocation: Option<GeoLocation>,
+    pub accuracy: Accuracy,
+}
+
+impl PostalRecordView<'_> {
+    /// Copy the record into an owned `PostalData` struct.
+    pub fn to_postal_data(&self) -> PostalData {
+        PostalData {
+            country_code: self.country_code.to_string(),
+            postal_code: self.postal_code.to_string(),
+            place_name: self.place_name.map(str::to_string),
+            admin_name1: self.admin_name1.map(str::to_string),
+            admin_code1: self.admin_code1.map(str::to_string),
+            admin_name2: self.admin_name2.map(str::to_string),
+            admin_code2: self.admin_code2.map(str::to_string),
+            admin_name3: self.admin_name3.map(str::to_string),
+            admin_code3: self.admin_code3.map(str::to_string),
+            geolocation: self.geolocation.clone(),
+            accuracy: self.accuracy.clone(),
+        }
+    }
+}
+
+impl Geolocatable for PostalRecordView<'_> {
+    fn geolocation(&self) -> Option<&GeoLocation> {
+        self.geolocation.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nearest;
+    use crate::tests::{essex_postal_fixture, temp_path};
+
+    use super::*;
+
+    #[test_log::test]
+    fn test_postal_binary_round_trip() {
+        let mut data = essex_postal_fixture();
+        data[2].admin_code2 = None;
+        data[2].geolocation = None;
+
+        let path = temp_path("binary.bin");
+        write_postal_binary(&data, File::create(&path).unwrap()).unwrap();
+
+        let binary = PostalBinary::open(&path).unwrap();
+        assert_eq!(binary.len(), 3);
+        let records: Vec<PostalRecordView> = binary.iter().collect();
+        for (view, record) in records.iter().zip(&data) {
+            assert_eq!(view.to_postal_data(), *record);
+        }
+        assert!(binary.get(3).is_none());
+
+        let nearest = nearest(GeoLocation::from((51.79, 0.63)), &records).unwrap();
+        assert_eq!(nearest.postal_code, "CM8");
+
+        std::fs::write(&path, b"not a binary file").unwrap();
+        assert!(PostalBinary::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt::Display;
+
+/// Errors returned by the fallible functions in this crate.
+#[derive(Debug)]
+pub enum GeoError {
+    // an I/O error while reading or writing data
+    Io(std::io::Error),
+    // an HTTP error while downloading data
+    Http(reqwest::Error),
+    // an error while extracting a downloaded archive
+    Zip(zip::result::ZipError),
+    // an error while parsing a GeoNames record
+    Parse(ParseError),
+    // the dataset contains no records
+    EmptyDataset,
+    // the dataset contains records, but none of them have a geolocation
+    NoGeolocatedRecords,
+    // offline mode is enabled and the data has not been cached at this path
+    OfflineCacheMiss(std::path::PathBuf),
+    // the cached data at this path is missing or does not match the pinned manifest
+    CacheMismatch(std::path::PathBuf),
+    // a downloaded archive does not contain the expected data file
+    FileNotInArchive {
+        expected: String,
+        available: Vec<String>,
+    },
+    // the dataset is too large to load into memory without opting in, use the streaming API
+    DatasetTooLargeWithoutStreaming,
+    // an error while writing CSV data
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
+    // an error while writing JSON data
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    // an error while reading or writing a SQLite database
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+}
+
+impl Display for GeoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeoError::Io(e) => write!(f, "I/O error: {}", e),
+            GeoError::Http(e) => write!(f, "HTTP error: {}", e),
+            GeoError::Zip(e) => write!(f, "Zip error: {}", e),
+            GeoError::Parse(e) => write!(f, "Parse error: {}", e),
+            GeoError::EmptyDataset => write!(f, "The dataset is empty"),
+            GeoError::NoGeolocatedRecords => {
+                write!(f, "The dataset contains no records with a geolocation")
+            }
+            GeoError::OfflineCacheMiss(path) => {
+                write!(
+                    f,
+                    "Offline mode is enabled and {} is not cached",
+                    path.display()
+                )
+            }
+            GeoError::CacheMismatch(path) => {
+                write!(
+                    f,
+                    "{} is missing or does not match the cache manifest",
+                    path.display()
+                )
+            }
+            GeoError::FileNotInArchive {
+                expected,
+                available,
+            } => {
+                write!(
+                    f,
+                    "{} not found in archive, which contains: {}",
+                    expected,
+                    available.join(", ")
+                )
+            }
+            GeoError::DatasetTooLargeWithoutStreaming => {
+                write!(
+                    f,
+                    "The dataset is too large to load into memory; use stream_all_countries_postal instead"
+                )
+            }
+            #[cfg(feature = "csv")]
+            GeoError::Csv(e) => write!(f, "CSV error: {}", e),
+            #[cfg(feature = "serde")]
+            GeoError::Json(e) => write!(f, "JSON error: {}", e),
+            #[cfg(feature = "sqlite")]
+            GeoError::Sqlite(e) => write!(f, "SQLite error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GeoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GeoError::Io(e) => Some(e),
+            GeoError::Http(e) => Some(e),
+            GeoError::Zip(e) => Some(e),
+            GeoError::Parse(e) => Some(e),
+            GeoError::EmptyDataset
+            | GeoError::NoGeolocatedRecords
+            | GeoError::OfflineCacheMiss(_)
+            | GeoError::CacheMismatch(_)
+            | GeoError::FileNotInArchive { .. }
+            | GeoError::DatasetTooLargeWithoutStreaming => None,
+            #[cfg(feature = "csv")]
+            GeoError::Csv(e) => Some(e),
+            #[cfg(feature = "serde")]
+            GeoError::Json(e) => Some(e),
+            #[cfg(feature = "sqlite")]
+            GeoError::Sqlite(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for GeoError {
+    fn from(e: std::io::Error) -> Self {
+        GeoError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for GeoError {
+    fn from(e: reqwest::Error) -> Self {
+        GeoError::Http(e)
+    }
+}
+
+impl From<zip::result::ZipError> for GeoError {
+    fn from(e: zip::result::ZipError) -> Self {
+        GeoError::Zip(e)
+    }
+}
+
+impl From<ParseError> for GeoError {
+    fn from(e: ParseError) -> Self {
+        GeoError::Parse(e)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for GeoError {
+    fn from(e: csv::Error) -> Self {
+        GeoError::Csv(e)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for GeoError {
+    fn from(e: serde_json::Error) -> Self {
+        GeoError::Json(e)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for GeoError {
+    fn from(e: rusqlite::Error) -> Self {
+        GeoError::Sqlite(e)
+    }
+}
+
+/// Errors returned while parsing GeoNames records.
+///
+/// Line numbers are 1-based and field indices are 0-based.
+#[derive(Debug)]
+pub enum ParseError {
+    // a required field is missing from a row
+    MissingField {
+        line: usize,
+        index: usize,
+    },
+    // a field could not be parsed into its expected type
+    InvalidField {
+        line: usize,
+        index: usize,
+        value: String,
+    },
+    // an I/O error while reading a row
+    Io {
+        line: usize,
+        error: std::io::Error,
+    },
+    // the first row has fewer columns than the layout being parsed needs
+    UnexpectedColumnCount {
+        line: usize,
+        found: usize,
+        expected: usize,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::MissingField { line, index } => {
+                write!(f, "line {}: missing field {}", line, index)
+            }
+            ParseError::InvalidField { line, index, value } => {
+                write!(
+                    f,
+                    "line {}: invalid value {:?} in field {}",
+                    line, value, index
+                )
+            }
+            ParseError::Io { line, error } => write!(f, "line {}: {}", line, error),
+            ParseError::UnexpectedColumnCount {
+                line,
+                found,
+                expected,
+            } => {
+                write!(
+                    f,
+                    "line {}: found {} columns, expected at least {}",
+                    line, found, expected
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
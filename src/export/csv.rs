@@ -0,0 +1,151 @@
+use std::io::Write;
+
+use crate::geonames::{GAZETTEER_COLUMNS, POSTAL_COLUMNS};
+use crate::{Gazetteer, GeoError, GeoLocation, PostalData};
+
+fn writer<W: Write>(writer: W) -> csv::Writer<W> {
+    // match the GeoNames layout so the output can be read back by the loaders
+    csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .quote_style(csv::QuoteStyle::Never)
+        .from_writer(writer)
+}
+
+fn coordinates(geolocation: &Option<GeoLocation>) -> (String, String) {
+    match geolocation {
+        Some(location) => (
+            location.latitude.to_string(),
+            location.longitude.to_string(),
+        ),
+        None => (String::new(), String::new()),
+    }
+}
+
+/// Write `PostalData` records as CSV.
+///
+/// The output is tab-separated with a header row and the same columns as the GeoNames
+/// postal files, so it can be read back with `load_postal_data`.
+///
+/// # Arguments
+///
+/// * `records` - A slice of `&PostalData` references, as returned by the radius functions.
+/// * `writer` - The `Write` implementation to write to.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the records were written.
+pub fn write_postal_csv<W: Write>(records: &[&PostalData], writer: W) -> Result<(), GeoError> {
+    let mut writer = self::writer(writer);
+    writer.write_record(POSTAL_COLUMNS)?;
+
+    for record in records {
+        let (latitude, longitude) = coordinates(&record.geolocation);
+        writer.write_record([
+            record.country_code.as_str(),
+            record.postal_code.as_str(),
+            record.place_name.as_deref().unwrap_or_default(),
+            record.admin_name1.as_deref().unwrap_or_default(),
+            record.admin_code1.as_deref().unwrap_or_default(),
+            record.admin_name2.as_deref().unwrap_or_default(),
+            record.admin_code2.as_deref().unwrap_or_default(),
+            record.admin_name3.as_deref().unwrap_or_default(),
+            record.admin_code3.as_deref().unwrap_or_default(),
+            latitude.as_str(),
+            longitude.as_str(),
+            &record
+                .accuracy
+                .code()
+                .map(|code| code.to_string())
+                .unwrap_or_default(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write `Gazetteer` records as CSV.
+///
+/// The output is tab-separated with a header row and the same columns as the GeoNames
+/// gazetteer files, so it can be read back with `load_gazetteer_data`.
+///
+/// # Arguments
+///
+/// * `records` - A slice of `&Gazetteer` references.
+/// * `writer` - The `Write` implementation to write to.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the records were written.
+pub fn write_gazetteer_csv<W: Write>(records: &[&Gazetteer], writer: W) -> Result<(), GeoError> {
+    let mut writer = self::writer(writer);
+    writer.write_record(GAZETTEER_COLUMNS)?;
+
+    for record in records {
+        let (latitude, longitude) = coordinates(&record.geolocation);
+        writer.write_record([
+            record.id.to_string().as_str(),
+            record.name.as_str(),
+            record.asciiname.as_str(),
+            record.alternate_names.join(",").as_str(),
+            latitude.as_str(),
+            longitude.as_str(),
+            record.feature_class.as_str(),
+            record.feature_code.as_str(),
+            record.country_code.as_str(),
+            record.alternate_country_codes.join(",").as_str(),
+            record.admin1_code.as_deref().unwrap_or_default(),
+            record.admin2_code.as_deref().unwrap_or_default(),
+            record.admin3_code.as_deref().unwrap_or_default(),
+            record.admin4_code.as_deref().unwrap_or_default(),
+            record.population.to_string().as_str(),
+            record.elevation.to_string().as_str(),
+            record.dem.to_string().as_str(),
+            record.timezone.as_str(),
+            record
+                .modification_date
+                .format("%Y-%m-%d")
+                .to_string()
+                .as_str(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::essex_postal_fixture;
+    use crate::{load_gazetteer_data, load_postal_data};
+
+    use super::*;
+
+    #[test_log::test]
+    fn test_postal_csv_round_trip() {
+        let data = essex_postal_fixture();
+        let records: Vec<&PostalData> = data.iter().collect();
+
+        let mut output = Vec::new();
+        write_postal_csv(&records, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("country_code\tpostal_code\t"));
+        assert_eq!(load_postal_data(&output), data);
+    }
+
+    #[test_log::test]
+    fn test_gazetteer_csv_round_trip() {
+        let data = load_gazetteer_data(
+            "2634633\tWitham\tWitham\tUitam,Witham\t51.80007\t0.64038\tP\tPPL\tGB\t\tENG\t11\t22UC\t\t25353\t0\t30\tEurope/London\t2018-07-03\n",
+        );
+        let records: Vec<&Gazetteer> = data.iter().collect();
+
+        let mut output = Vec::new();
+        write_gazetteer_csv(&records, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("geonameid\tname\t"));
+        assert_eq!(load_gazetteer_data(&output), data);
+    }
+}
@@ -0,0 +1,241 @@
+use geojson::{Feature, FeatureCollection, Geometry, JsonObject, JsonValue, Value};
+
+use crate::{Gazetteer, GeoLocation, PostalData};
+
+fn point(location: &GeoLocation) -> Geometry {
+    // GeoJSON positions are ordered longitude, latitude
+    Geometry::new(Value::Point(vec![location.longitude, location.latitude]))
+}
+
+fn feature(location: &GeoLocation, properties: JsonObject) -> Feature {
+    Feature {
+        bbox: None,
+        geometry: Some(point(location)),
+        id: None,
+        properties: Some(properties),
+        foreign_members: None,
+    }
+}
+
+/// Convert a `PostalData` struct into a GeoJSON `Feature`.
+///
+/// # Arguments
+///
+/// * `postal_data` - A `PostalData` struct.
+///
+/// # Returns
+///
+/// An `Option` containing a `Feature` with a `Point` geometry, or `None` if the record has no geolocation.
+pub fn postal_data_to_feature(postal_data: &PostalData) -> Option<Feature> {
+    let location = postal_data.geolocation.as_ref()?;
+
+    let mut properties = JsonObject::new();
+    properties.insert(
+        "country_code".to_string(),
+        JsonValue::from(postal_data.country_code.clone()),
+    );
+    properties.insert(
+        "postal_code".to_string(),
+        JsonValue::from(postal_data.postal_code.clone()),
+    );
+    properties.insert(
+        "place_name".to_string(),
+        JsonValue::from(postal_data.place_name.clone()),
+    );
+    properties.insert(
+        "admin_name1".to_string(),
+        JsonValue::from(postal_data.admin_name1.clone()),
+    );
+    properties.insert(
+        "admin_code1".to_string(),
+        JsonValue::from(postal_data.admin_code1.clone()),
+    );
+    properties.insert(
+        "admin_name2".to_string(),
+        JsonValue::from(postal_data.admin_name2.clone()),
+    );
+    properties.insert(
+        "admin_code2".to_string(),
+        JsonValue::from(postal_data.admin_code2.clone()),
+    );
+    properties.insert(
+        "admin_name3".to_string(),
+        JsonValue::from(postal_data.admin_name3.clone()),
+    );
+    properties.insert(
+        "admin_code3".to_string(),
+        JsonValue::from(postal_data.admin_code3.clone()),
+    );
+    properties.insert(
+        "accuracy".to_string(),
+        JsonValue::from(postal_data.accuracy.to_string()),
+    );
+
+    Some(feature(location, properties))
+}
+
+/// Convert a `Gazetteer` struct into a GeoJSON `Feature`.
+///
+/// # Arguments
+///
+/// * `gazetteer` - A `Gazetteer` struct.
+///
+/// # Returns
+///
+/// An `Option` containing a `Feature` with a `Point` geometry, or `None` if the record has no geolocation.
+pub fn gazetteer_to_feature(gazetteer: &Gazetteer) -> Option<Feature> {
+    let location = gazetteer.geolocation.as_ref()?;
+
+    let mut properties = JsonObject::new();
+    properties.insert("id".to_string(), JsonValue::from(gazetteer.id));
+    properties.insert("name".to_string(), JsonValue::from(gazetteer.name.clone()));
+    properties.insert(
+        "asciiname".to_string(),
+        JsonValue::from(gazetteer.asciiname.clone()),
+    );
+    properties.insert(
+        "alternate_names".to_string(),
+        JsonValue::from(gazetteer.alternate_names.clone()),
+    );
+    properties.insert(
+        "feature_class".to_string(),
+        JsonValue::from(gazetteer.feature_class.clone()),
+    );
+    properties.insert(
+        "feature_code".to_string(),
+        JsonValue::from(gazetteer.feature_code.clone()),
+    );
+    properties.insert(
+        "country_code".to_string(),
+        JsonValue::from(gazetteer.country_code.clone()),
+    );
+    properties.insert(
+        "alternate_country_codes".to_string(),
+        JsonValue::from(gazetteer.alternate_country_codes.clone()),
+    );
+    properties.insert(
+        "admin1_code".to_string(),
+        JsonValue::from(gazetteer.admin1_code.clone()),
+    );
+    properties.insert(
+        "admin2_code".to_string(),
+        JsonValue::from(gazetteer.admin2_code.clone()),
+    );
+    properties.insert(
+        "admin3_code".to_string(),
+        JsonValue::from(gazetteer.admin3_code.clone()),
+    );
+    properties.insert(
+        "admin4_code".to_string(),
+        JsonValue::from(gazetteer.admin4_code.clone()),
+    );
+    properties.insert(
+        "population".to_string(),
+        JsonValue::from(gazetteer.population),
+    );
+    properties.insert(
+        "elevation".to_string(),
+        JsonValue::from(gazetteer.elevation),
+    );
+    properties.insert("dem".to_string(), JsonValue::from(gazetteer.dem));
+    properties.insert(
+        "timezone".to_string(),
+        JsonValue::from(gazetteer.timezone.clone()),
+    );
+    properties.insert(
+        "modification_date".to_string(),
+        JsonValue::from(gazetteer.modification_date.to_string()),
+    );
+
+    Some(feature(location, properties))
+}
+
+/// Convert a slice of `PostalData` structs into a GeoJSON `FeatureCollection`.
+///
+/// Records without a geolocation are skipped.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `FeatureCollection` containing a `Point` feature for each geolocated record.
+pub fn postal_data_to_feature_collection(geonames_data: &[PostalData]) -> FeatureCollection {
+    FeatureCollection {
+        bbox: None,
+        features: geonames_data
+            .iter()
+            .filter_map(postal_data_to_feature)
+            .collect(),
+        foreign_members: None,
+    }
+}
+
+/// Convert a slice of `Gazetteer` structs into a GeoJSON `FeatureCollection`.
+///
+/// Records without a geolocation are skipped.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// A `FeatureCollection` containing a `Point` feature for each geolocated record.
+pub fn gazetteer_to_feature_collection(geonames_data: &[Gazetteer]) -> FeatureCollection {
+    FeatureCollection {
+        bbox: None,
+        features: geonames_data
+            .iter()
+            .filter_map(gazetteer_to_feature)
+            .collect(),
+        foreign_members: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Accuracy;
+
+    use super::*;
+
+    #[test_log::test]
+    fn test_postal_data_to_feature_collection() {
+        let located = PostalData {
+            country_code: "GB".to_string(),
+            postal_code: "CM8".to_string(),
+            place_name: Some("Witham".to_string()),
+            admin_name1: Some("England".to_string()),
+            admin_code1: Some("ENG".to_string()),
+            admin_name2: Some("Essex".to_string()),
+            admin_code2: None,
+            admin_name3: None,
+            admin_code3: None,
+            geolocation: Some(GeoLocation {
+                latitude: 51.8,
+                longitude: 0.63,
+            }),
+            accuracy: Accuracy::Centroid,
+        };
+        let unlocated = PostalData {
+            postal_code: "CM9".to_string(),
+            geolocation: None,
+            ..located.clone()
+        };
+
+        let collection = postal_data_to_feature_collection(&[located, unlocated]);
+
+        assert_eq!(collection.features.len(), 1);
+        let feature = &collection.features[0];
+        assert_eq!(
+            feature.geometry.as_ref().unwrap().value,
+            Value::Point(vec![0.63, 51.8])
+        );
+        assert_eq!(
+            feature.property("postal_code"),
+            Some(&JsonValue::from("CM8"))
+        );
+        assert_eq!(feature.property("admin_code2"), Some(&JsonValue::Null));
+    }
+}
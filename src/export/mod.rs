@@ -0,0 +1,13 @@
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "geojson")]
+mod geojson;
+#[cfg(feature = "serde")]
+mod ndjson;
+
+#[cfg(feature = "csv")]
+pub use self::csv::*;
+#[cfg(feature = "geojson")]
+pub use self::geojson::*;
+#[cfg(feature = "serde")]
+pub use self::ndjson::*;
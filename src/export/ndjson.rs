@@ -0,0 +1,59 @@
+use std::io::{BufWriter, Write};
+
+use crate::{GeoError, PostalData};
+
+/// Write `PostalData` records as newline-delimited JSON.
+///
+/// Each record is written as one JSON object per line as soon as it is read from the
+/// iterator, so it pairs with `for_each_postal_record` and `stream_all_countries_postal`
+/// to convert large datasets without holding them in memory.
+///
+/// # Arguments
+///
+/// * `records` - An iterator of `&PostalData` references.
+/// * `writer` - The `Write` implementation to write to.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the records were written.
+pub fn write_postal_ndjson<'a, W: Write>(
+    records: impl Iterator<Item = &'a PostalData>,
+    writer: W,
+) -> Result<(), GeoError> {
+    let mut writer = BufWriter::new(writer);
+
+    for record in records {
+        serde_json::to_writer(&mut writer, record)?;
+        writer.write_all(b"\n")?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::essex_postal_fixture;
+
+    use super::*;
+
+    #[test_log::test]
+    fn test_write_postal_ndjson() {
+        let mut data = essex_postal_fixture();
+        data[1].geolocation = None;
+
+        let mut output = Vec::new();
+        write_postal_ndjson(data.iter(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["postal_code"], "CM8");
+        assert_eq!(lines[0]["geolocation"]["latitude"], 51.8);
+        assert_eq!(lines[0]["accuracy"], "Centroid");
+        assert!(lines[1]["geolocation"].is_null());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    country_at, get_gazetteer_data_with_config, get_nearest_place, get_nearest_postcode,
+    get_place_location, get_places_within_radius, get_postal_data_with_config,
+    get_postcode_location, get_postcodes_within_radius, Country, DownloadConfig, Gazetteer,
+    GeoError, GeoLocation, PostalData,
+};
+
+/// Postal and gazetteer data loaded once and queried together.
+///
+/// Each method answers a query with the free function of the same purpose, so a `Geocoder`
+/// returns the same results as calling those functions on its data. Use `postal` and
+/// `gazetteer` to pass the data to functions without a method here, or to build a `GeoGrid`
+/// for repeated radius queries on large datasets.
+#[derive(Debug, Clone, Default)]
+pub struct Geocoder {
+    postal: Vec<PostalData>,
+    gazetteer: Vec<Gazetteer>,
+}
+
+impl Geocoder {
+    /// Create a new `Geocoder` by downloading the data for several countries.
+    ///
+    /// Gazetteer data is skipped for countries GeoNames does not publish it for, such as the
+    /// `*Full` postal variants.
+    ///
+    /// # Arguments
+    ///
+    /// * `countries` - A slice of `Country` enums to load data for.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Geocoder`, or the `GeoError` for the first country that
+    /// could not be loaded.
+    pub fn new(countries: &[Country]) -> Result<Self, GeoError> {
+        Self::with_config(countries, &DownloadConfig::default())
+    }
+
+    /// Create a new `Geocoder` by downloading the data for several countries using a custom
+    /// configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `countries` - A slice of `Country` enums to load data for.
+    /// * `config` - A `DownloadConfig` controlling how the data is fetched.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Geocoder`, or the `GeoError` for the first country that
+    /// could not be loaded.
+    pub fn with_config(countries: &[Country], config: &DownloadConfig) -> Result<Self, GeoError> {
+        let mut geocoder = Geocoder::default();
+
+        for country in countries {
+            geocoder
+                .postal
+                .extend(get_postal_data_with_config(country.clone(), config)?);
+            if country.supports_gazetteer() {
+                geocoder
+                    .gazetteer
+                    .extend(get_gazetteer_data_with_config(country.clone(), config)?);
+            }
+        }
+
+        log::debug!(
+            "Loaded {} postal and {} gazetteer records",
+            geocoder.postal.len(),
+            geocoder.gazetteer.len()
+        );
+
+        Ok(geocoder)
+    }
+
+    /// Create a new `Geocoder` from data that has already been loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `postal` - A `Vec` of `PostalData` structs.
+    /// * `gazetteer` - A `Vec` of `Gazetteer` structs.
+    ///
+    /// # Returns
+    ///
+    /// A `Geocoder` owning the data.
+    pub fn from_data(postal: Vec<PostalData>, gazetteer: Vec<Gazetteer>) -> Self {
+        Geocoder { postal, gazetteer }
+    }
+
+    /// Get the postal data.
+    pub fn postal(&self) -> &[PostalData] {
+        &self.postal
+    }
+
+    /// Get the gazetteer data.
+    pub fn gazetteer(&self) -> &[Gazetteer] {
+        &self.gazetteer
+    }
+
+    /// Get the nearest postcode to a location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `Location` struct representing the location.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a reference to the nearest `PostalData` struct.
+    pub fn nearest_postcode(&self, location: GeoLocation) -> Option<&PostalData> {
+        get_nearest_postcode(location, &self.postal)
+    }
+
+    /// Get the nearest place to a location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `Location` struct representing the location.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a reference to the nearest `Gazetteer` struct.
+    pub fn nearest_place(&self, location: GeoLocation) -> Option<&Gazetteer> {
+        get_nearest_place(location, &self.gazetteer)
+    }
+
+    /// Get all postcodes within a radius of a location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `Location` struct representing the location.
+    /// * `radius` - A `f64` representing the radius in kilometers.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of `&str` containing the postcodes.
+    pub fn postcodes_within_radius(&self, location: GeoLocation, radius: f64) -> Vec<&str> {
+        get_postcodes_within_radius(location, radius, &self.postal)
+    }
+
+    /// Get all places within a radius of a location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `Location` struct representing the location.
+    /// * `radius` - A `f64` representing the radius in kilometers.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of `&str` containing the places.
+    pub fn places_within_radius(&self, location: GeoLocation, radius: f64) -> Vec<&str> {
+        get_places_within_radius(location, radius, &self.gazetteer)
+    }
+
+    /// Get the location of a postcode.
+    ///
+    /// # Arguments
+    ///
+    /// * `postcode` - A `&str` representing the postcode.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a `GeoLocation` struct.
+    pub fn postcode_location(&self, postcode: &str) -> Option<GeoLocation> {
+        get_postcode_location(postcode, &self.postal)
+    }
+
+    /// Get the location of a place.
+    ///
+    /// # Arguments
+    ///
+    /// * `place` - A `&str` representing the place.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a `GeoLocation` struct.
+    pub fn place_location(&self, place: &str) -> Option<GeoLocation> {
+        get_place_location(place, &self.gazetteer)
+    }
+
+    /// Guess the country containing a location from the nearest postcode.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `Location` struct representing the location.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the ISO country code of the nearest postcode.
+    pub fn country_at(&self, location: GeoLocation) -> Option<&str> {
+        country_at(location, &self.postal)
+    }
+}
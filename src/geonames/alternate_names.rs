@@ -0,0 +1,134 @@
+use crate::geonames::{data_lines, download, download_with_config, Data, DownloadConfig};
+use crate::models::AlternateName;
+use crate::{Country, GeoError};
+
+const GEONAMES_ALTERNATE_NAMES_URL_BASE: &str = "https://download.geonames.org/export/dump";
+
+pub(crate) fn get_alternate_names_url(country: &Country) -> String {
+    if !country.supports_gazetteer() {
+        panic!("Country {} not supported for alternate names", country)
+    }
+
+    match country {
+        Country::All => format!("{}/alternateNamesV2.zip", GEONAMES_ALTERNATE_NAMES_URL_BASE),
+        _ => format!(
+            "{}/alternatenames/{}.zip",
+            GEONAMES_ALTERNATE_NAMES_URL_BASE, country
+        ),
+    }
+}
+
+pub(crate) fn get_alternate_names_file_name(country: &Country) -> String {
+    match country {
+        Country::All => "alternateNamesV2.txt".to_string(),
+        _ => format!("{}.txt", country),
+    }
+}
+
+fn optional(field: Option<&&str>) -> Option<String> {
+    field
+        .filter(|field| !field.is_empty())
+        .map(|field| field.to_string())
+}
+
+fn flag(field: Option<&&str>) -> bool {
+    field == Some(&"1")
+}
+
+/// Parse GeoNames alternate names data.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames alternate names records.
+///
+/// # Returns
+///
+/// A `Vec` of `AlternateName` structs.
+pub fn load_alternate_names_data(data: &str) -> Vec<AlternateName> {
+    log::debug!("Parsing geonames data");
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "parse",
+        data_type = "alternate_names",
+        records = tracing::field::Empty
+    )
+    .entered();
+    let data: Vec<AlternateName> = data_lines(data)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            AlternateName {
+                id: fields[0].parse().unwrap(),
+                geoname_id: fields[1].parse().unwrap(),
+                iso_language: optional(fields.get(2)),
+                name: fields[3].to_string(),
+                is_preferred_name: flag(fields.get(4)),
+                is_short_name: flag(fields.get(5)),
+                is_colloquial: flag(fields.get(6)),
+                is_historic: flag(fields.get(7)),
+                from: optional(fields.get(8)),
+                to: optional(fields.get(9)),
+            }
+        })
+        .collect();
+
+    log::debug!("Parsed {} records", data.len());
+    #[cfg(feature = "tracing")]
+    span.record("records", data.len());
+
+    data
+}
+
+/// Get alternate names data for a specific country.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+///
+/// # Returns
+///
+/// A `Vec` of `AlternateName` structs.
+pub fn get_alternate_names_data(country: Country) -> Vec<AlternateName> {
+    let data = download(&country, Data::AlternateNames).unwrap();
+    load_alternate_names_data(&data)
+}
+
+/// Get alternate names data for a specific country using a custom configuration.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `config` - A `DownloadConfig` controlling how the data is fetched.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `AlternateName` structs.
+pub fn get_alternate_names_data_with_config(
+    country: Country,
+    config: &DownloadConfig,
+) -> Result<Vec<AlternateName>, GeoError> {
+    let data = download_with_config(&country, Data::AlternateNames, config)?;
+    Ok(load_alternate_names_data(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_load_alternate_names_data() {
+        let data = load_alternate_names_data(
+            "1557374\t2867714\tde\tMünchen\t1\t\t\t\t\t\n\
+             2697486\t2867714\t\tMunich\t\t\t\t\t\t\n\
+             16816806\t2867714\tlink\thttps://en.wikipedia.org/wiki/Munich\t\t\t\t\t\t\n",
+        );
+
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[0].geoname_id, 2867714);
+        assert_eq!(data[0].iso_language.as_deref(), Some("de"));
+        assert_eq!(data[0].name, "München");
+        assert!(data[0].is_preferred_name);
+        assert!(!data[0].is_historic);
+        assert_eq!(data[1].iso_language, None);
+    }
+}
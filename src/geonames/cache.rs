@@ -0,0 +1,369 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::geonames::{
+    download_with_status, get_temp_dir, Data, DownloadConfig, LoadResult, MANIFEST_FILE_NAME,
+};
+use crate::{Country, GeoError};
+
+/// The default number of concurrent downloads used by `warm_cache`.
+pub const WARM_CACHE_THREADS: usize = 4;
+
+/// Get the directory used to cache downloaded data.
+///
+/// This is the `GEOCODER_CACHE_DIR` environment variable if set, otherwise a directory
+/// named `geonames` in the system's temporary directory.
+///
+/// # Returns
+///
+/// A `PathBuf` representing the cache directory.
+pub fn cache_dir() -> PathBuf {
+    match std::env::var("GEOCODER_CACHE_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from(get_temp_dir()),
+    }
+}
+
+pub(crate) fn data_cache_dir_in(root: &Path, data_type: Data) -> PathBuf {
+    match data_type {
+        Data::Postal => root.join("postal"),
+        Data::Gazetteer => root.join("gazetteer"),
+        Data::AlternateNames => root.join("alternate_names"),
+    }
+}
+
+pub(crate) fn cache_path_in(root: &Path, country: &Country, data_type: Data) -> PathBuf {
+    data_cache_dir_in(root, data_type).join(format!("{}.txt", country))
+}
+
+/// Check whether a cached file was written less than `max_age` ago.
+///
+/// Files whose modification time cannot be read are treated as stale.
+pub(crate) fn is_fresh(path: &Path, max_age: Duration) -> bool {
+    let age = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| modified.elapsed().unwrap_or_default());
+
+    match age {
+        Ok(age) if age <= max_age => true,
+        Ok(_) => {
+            log::debug!("Cached file {} is stale", path.display());
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+fn dir_size(path: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Get the total size of the cache directory.
+///
+/// # Returns
+///
+/// A `Result` containing the size of all cached files in bytes, or 0 if nothing has been cached.
+pub fn cache_size_bytes() -> std::io::Result<u64> {
+    cache_size_bytes_in(&cache_dir())
+}
+
+/// Get the total size of the cache directory set in a `DownloadConfig`.
+///
+/// # Arguments
+///
+/// * `config` - A `DownloadConfig` whose `cache_location` is inspected.
+///
+/// # Returns
+///
+/// A `Result` containing the size of all cached files in bytes, or 0 if nothing has been cached.
+pub fn cache_size_bytes_with_config(config: &DownloadConfig) -> std::io::Result<u64> {
+    cache_size_bytes_in(&config.cache_location())
+}
+
+fn cache_size_bytes_in(root: &Path) -> std::io::Result<u64> {
+    if !root.exists() {
+        return Ok(0);
+    }
+    dir_size(root)
+}
+
+/// List the countries with cached data.
+///
+/// # Arguments
+///
+/// * `data_type` - A `Data` enum representing the type of data.
+///
+/// # Returns
+///
+/// A `Vec` of `Country` enums that have cached data of the given type.
+pub fn list_cached_countries(data_type: Data) -> Vec<Country> {
+    list_cached_countries_in(&cache_dir(), data_type)
+}
+
+/// List the countries with cached data in the cache directory set in a `DownloadConfig`.
+///
+/// # Arguments
+///
+/// * `data_type` - A `Data` enum representing the type of data.
+/// * `config` - A `DownloadConfig` whose `cache_location` is inspected.
+///
+/// # Returns
+///
+/// A `Vec` of `Country` enums that have cached data of the given type.
+pub fn list_cached_countries_with_config(data_type: Data, config: &DownloadConfig) -> Vec<Country> {
+    list_cached_countries_in(&config.cache_location(), data_type)
+}
+
+pub(crate) fn list_cached_countries_in(root: &Path, data_type: Data) -> Vec<Country> {
+    let Ok(entries) = std::fs::read_dir(data_cache_dir_in(root, data_type)) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .filter_map(|path| Country::from_str(path.file_stem()?.to_str()?).ok())
+        .collect()
+}
+
+/// Invalidate the cached data for a single country.
+///
+/// This removes the cached `<country>.txt` file and any `.bin` sidecar next to it.
+/// Files that are not cached are ignored.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `data_type` - A `Data` enum representing the type of data.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the cached files could be removed.
+pub fn invalidate_country(country: &Country, data_type: Data) -> std::io::Result<()> {
+    invalidate_country_in(&cache_dir(), country, data_type)
+}
+
+/// Invalidate the cached data for a single country in the cache directory set in a
+/// `DownloadConfig`.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `data_type` - A `Data` enum representing the type of data.
+/// * `config` - A `DownloadConfig` whose `cache_location` is invalidated.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the cached files could be removed.
+pub fn invalidate_country_with_config(
+    country: &Country,
+    data_type: Data,
+    config: &DownloadConfig,
+) -> std::io::Result<()> {
+    invalidate_country_in(&config.cache_location(), country, data_type)
+}
+
+fn invalidate_country_in(root: &Path, country: &Country, data_type: Data) -> std::io::Result<()> {
+    let cache_path = cache_path_in(root, country, data_type);
+
+    for path in [cache_path.with_extension("bin"), cache_path] {
+        match std::fs::remove_file(&path) {
+            Ok(()) => log::debug!("Removed cached file {}", path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Invalidate the cache.
+///
+/// This function will remove any cached data that has been downloaded, along with the
+/// cache manifest. Use `invalidate_country` to remove the data for a single country.
+/// Data that has already been removed, for example by another process, is ignored.
+///
+/// # Returns
+///
+/// A `Result` containing the first error that prevented the cache from being removed.
+pub fn invalidate_cache() -> std::io::Result<()> {
+    invalidate_cache_in(&cache_dir())
+}
+
+/// Invalidate the cache directory set in a `DownloadConfig`.
+///
+/// # Arguments
+///
+/// * `config` - A `DownloadConfig` whose `cache_location` is invalidated.
+///
+/// # Returns
+///
+/// A `Result` containing the first error that prevented the cache from being removed.
+pub fn invalidate_cache_with_config(config: &DownloadConfig) -> std::io::Result<()> {
+    invalidate_cache_in(&config.cache_location())
+}
+
+fn invalidate_cache_in(root: &Path) -> std::io::Result<()> {
+    for data_type in [Data::Postal, Data::Gazetteer, Data::AlternateNames] {
+        let data_cache_dir = data_cache_dir_in(root, data_type);
+        match std::fs::remove_dir_all(&data_cache_dir) {
+            Ok(()) => log::debug!("Removed {:?} cache", data_type),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    match std::fs::remove_file(root.join(MANIFEST_FILE_NAME)) {
+        Ok(()) => log::debug!("Removed cache manifest"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    Ok(())
+}
+
+/// Download and cache data for several countries in parallel.
+///
+/// Countries that are already cached are not downloaded again. A failure for one country
+/// does not stop the others from being downloaded. The number of downloaded and cached
+/// countries is logged once every country has been handled.
+///
+/// # Arguments
+///
+/// * `countries` - A slice of `Country` enums to download data for.
+/// * `data_type` - A `Data` enum representing the type of data to download.
+///
+/// # Returns
+///
+/// A `Result` which, on failure, contains each country that could not be downloaded along with its error.
+pub fn warm_cache(countries: &[Country], data_type: Data) -> Result<(), Vec<(Country, GeoError)>> {
+    warm_cache_with_config(countries, data_type, &DownloadConfig::default())
+}
+
+/// Download and cache data for several countries in parallel using a custom configuration.
+///
+/// Every download shares a single HTTP client, either the one set with
+/// `DownloadConfig::client` or one built from the configuration, so connections are reused.
+/// At most `DownloadConfig::max_concurrent` countries are downloaded at once.
+///
+/// # Arguments
+///
+/// * `countries` - A slice of `Country` enums to download data for.
+/// * `data_type` - A `Data` enum representing the type of data to download.
+/// * `config` - A `DownloadConfig` controlling how the data is fetched.
+///
+/// # Returns
+///
+/// A `Result` which, on failure, contains each country that could not be downloaded along with its error.
+pub fn warm_cache_with_config(
+    countries: &[Country],
+    data_type: Data,
+    config: &DownloadConfig,
+) -> Result<(), Vec<(Country, GeoError)>> {
+    let config = match config.http_client() {
+        Ok(client) => config.clone().client(client),
+        Err(e) => {
+            log::warn!("Failed to build HTTP client: {}", e);
+            config.clone()
+        }
+    };
+    let queue = Mutex::new(countries.iter());
+    let errors = Mutex::new(Vec::new());
+    let results = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..config.concurrency().min(countries.len()) {
+            scope.spawn(|| loop {
+                let Some(country) = queue.lock().unwrap().next() else {
+                    break;
+                };
+
+                log::debug!("Warming {:?} cache for {}", data_type, country);
+                match download_with_status(country, data_type, &config) {
+                    Ok((_, result)) => results.lock().unwrap().push(result),
+                    Err(e) => {
+                        log::warn!("Failed to warm cache for {}: {}", country, e);
+                        errors.lock().unwrap().push((country.clone(), e));
+                    }
+                }
+            });
+        }
+    });
+
+    let results = results.into_inner().unwrap();
+    let downloaded = results
+        .iter()
+        .filter(|result| matches!(result, LoadResult::Downloaded(_)))
+        .count();
+    log::info!(
+        "Warmed {:?} cache: {} downloaded, {} cached",
+        data_type,
+        downloaded,
+        results.len() - downloaded
+    );
+
+    let errors = errors.into_inner().unwrap();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_invalidate_country_not_cached() {
+        invalidate_country(&Country::VaticanCity, Data::Gazetteer).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_invalidate_cache_in() {
+        let root = std::env::temp_dir().join(format!("geo_rust_invalidate_{}", std::process::id()));
+        let postal = data_cache_dir_in(&root, Data::Postal);
+        std::fs::create_dir_all(&postal).unwrap();
+        std::fs::write(postal.join("GB.txt"), "").unwrap();
+        std::fs::write(postal.join("allCountries.zip"), "").unwrap();
+        std::fs::write(root.join(MANIFEST_FILE_NAME), "").unwrap();
+
+        invalidate_cache_in(&root).unwrap();
+        assert!(!postal.exists());
+        assert!(!root.join(MANIFEST_FILE_NAME).exists());
+        // invalidating an empty cache is not an error
+        invalidate_cache_in(&root).unwrap();
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_is_fresh() {
+        let path = std::env::temp_dir().join(format!("geo_rust_fresh_{}.txt", std::process::id()));
+        assert!(!is_fresh(&path, Duration::from_secs(60)));
+
+        std::fs::write(&path, "").unwrap();
+        assert!(is_fresh(&path, Duration::from_secs(60)));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!is_fresh(&path, Duration::from_millis(10)));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_warm_cache() {
+        warm_cache(&[Country::Andorra, Country::Monaco], Data::Postal).unwrap();
+    }
+}
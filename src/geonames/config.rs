@@ -0,0 +1,307 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::geonames::cache::{self, WARM_CACHE_THREADS};
+use crate::geonames::Data;
+use crate::GeoError;
+
+/// The default timeout for connecting to, reading from and writing to GeoNames.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+/// The default timeout for connecting to GeoNames.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// The default user agent sent with download requests, `geocoder-rs/<version>`.
+pub const DEFAULT_USER_AGENT: &str = concat!("geocoder-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Settings used when fetching GeoNames data.
+///
+/// `DownloadConfig::default()` reads the `GEOCODER_*` environment variables, and the builder
+/// methods override them for a single call.
+#[derive(Debug, Clone)]
+pub struct DownloadConfig {
+    offline: bool,
+    timeout: Duration,
+    connect_timeout: Duration,
+    user_agent: String,
+    client: Option<reqwest::blocking::Client>,
+    max_concurrent: usize,
+    postal_max_age: Option<Duration>,
+    gazetteer_max_age: Option<Duration>,
+    alternate_names_max_age: Option<Duration>,
+    cache_dir: Option<PathBuf>,
+}
+
+/// Read a maximum age in seconds from an environment variable.
+fn max_age_from_env(key: &str) -> Option<Duration> {
+    let value = std::env::var(key).ok()?;
+    match value.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            log::warn!("Ignoring invalid {}: {:?}", key, value);
+            None
+        }
+    }
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        DownloadConfig {
+            offline: std::env::var("GEOCODER_OFFLINE").is_ok(),
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            client: None,
+            max_concurrent: WARM_CACHE_THREADS,
+            postal_max_age: max_age_from_env("GEOCODER_POSTAL_MAX_AGE"),
+            gazetteer_max_age: max_age_from_env("GEOCODER_GAZETTEER_MAX_AGE"),
+            alternate_names_max_age: max_age_from_env("GEOCODER_ALTERNATE_NAMES_MAX_AGE"),
+            cache_dir: None,
+        }
+    }
+}
+
+impl DownloadConfig {
+    /// Create a new `DownloadConfig` from the environment.
+    ///
+    /// # Returns
+    ///
+    /// A `DownloadConfig` with the same settings as `DownloadConfig::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only use cached data and never make a network request.
+    ///
+    /// Defaults to `true` if the `GEOCODER_OFFLINE` environment variable is set.
+    /// In offline mode cached data is used even if `DISABLE_GEOCODER_CACHE` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `offline` - A `bool` indicating whether to work offline.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Set the timeout for connecting to, reading from and writing to GeoNames.
+    ///
+    /// Defaults to `DEFAULT_TIMEOUT`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - A `Duration` representing the timeout.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the timeout for connecting to GeoNames.
+    ///
+    /// Defaults to `DEFAULT_CONNECT_TIMEOUT`.
+    ///
+    /// # Arguments
+    ///
+    /// * `connect_timeout` - A `Duration` representing the timeout.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Set the user agent sent with download requests.
+    ///
+    /// Defaults to `DEFAULT_USER_AGENT`.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent` - The user agent, e.g. `"my-app/1.0"`.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Use a pre-built HTTP client for every download.
+    ///
+    /// Sharing one client reuses its connection pool and TLS state across downloads, which
+    /// helps when fetching many countries. The client's own timeouts and user agent are used,
+    /// so `timeout`, `connect_timeout` and `user_agent` have no effect once a client is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A `reqwest::blocking::Client` to send requests with.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Set the maximum number of downloads `warm_cache_with_config` runs at once.
+    ///
+    /// Defaults to `WARM_CACHE_THREADS`. Keep this low to avoid being rate limited by
+    /// GeoNames; values below 1 are treated as 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrent` - A `usize` representing the number of concurrent downloads.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = max_concurrent.max(1);
+        self
+    }
+
+    /// Get the maximum number of concurrent downloads.
+    pub fn concurrency(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Set how long cached data of one type is used before it is downloaded again.
+    ///
+    /// Defaults to the `GEOCODER_POSTAL_MAX_AGE`, `GEOCODER_GAZETTEER_MAX_AGE` and
+    /// `GEOCODER_ALTERNATE_NAMES_MAX_AGE` environment variables, in seconds, and otherwise
+    /// cached data never expires. Stale data is still used in offline mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_type` - A `Data` enum representing the type of data.
+    /// * `max_age` - A `Duration` after which cached data is downloaded again.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn max_age(mut self, data_type: Data, max_age: Duration) -> Self {
+        *self.max_age_mut(data_type) = Some(max_age);
+        self
+    }
+
+    /// Get how long cached data of one type is used before it is downloaded again.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_type` - A `Data` enum representing the type of data.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the maximum age, or `None` if cached data never expires.
+    pub fn max_age_for(&self, data_type: Data) -> Option<Duration> {
+        match data_type {
+            Data::Postal => self.postal_max_age,
+            Data::Gazetteer => self.gazetteer_max_age,
+            Data::AlternateNames => self.alternate_names_max_age,
+        }
+    }
+
+    fn max_age_mut(&mut self, data_type: Data) -> &mut Option<Duration> {
+        match data_type {
+            Data::Postal => &mut self.postal_max_age,
+            Data::Gazetteer => &mut self.gazetteer_max_age,
+            Data::AlternateNames => &mut self.alternate_names_max_age,
+        }
+    }
+
+    /// Set the directory downloaded data is cached in.
+    ///
+    /// Defaults to `cache_dir()`, which reads the `GEOCODER_CACHE_DIR` environment variable.
+    /// Setting a directory here overrides both the environment variable and the temporary
+    /// directory, e.g. to keep the data in an application's data directory. Functions that
+    /// do not take a `DownloadConfig`, such as `list_cached_countries`, still use `cache_dir()`;
+    /// use their `_with_config` variants to inspect or invalidate this directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache_dir` - The directory to cache data in. It is created if it does not exist.
+    ///
+    /// # Returns
+    ///
+    /// The updated `DownloadConfig`.
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Get the directory downloaded data is cached in.
+    ///
+    /// # Returns
+    ///
+    /// A `PathBuf` representing the directory set with `cache_dir`, or `cache_dir()` if none was set.
+    pub fn cache_location(&self) -> PathBuf {
+        self.cache_dir.clone().unwrap_or_else(cache::cache_dir)
+    }
+
+    /// Check whether offline mode is enabled.
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub(crate) fn http_client(&self) -> Result<reqwest::blocking::Client, GeoError> {
+        if let Some(client) = &self.client {
+            // clients are reference counted, so this shares the connection pool
+            return Ok(client.clone());
+        }
+
+        Ok(reqwest::blocking::Client::builder()
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .user_agent(&self.user_agent)
+            .build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_client() {
+        let config = DownloadConfig::new()
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(1))
+            .user_agent("geo_rust-tests");
+
+        assert_eq!(config.user_agent, "geo_rust-tests");
+        assert!(config.http_client().is_ok());
+        assert_eq!(
+            DEFAULT_USER_AGENT,
+            format!("geocoder-rs/{}", env!("CARGO_PKG_VERSION"))
+        );
+        assert!(config.client.is_none());
+        assert_eq!(config.concurrency(), WARM_CACHE_THREADS);
+        assert_eq!(config.clone().max_concurrent(0).concurrency(), 1);
+
+        let config = config.max_age(Data::Postal, Duration::from_secs(3600));
+        assert_eq!(
+            config.max_age_for(Data::Postal),
+            Some(Duration::from_secs(3600))
+        );
+
+        assert_eq!(config.cache_location(), cache::cache_dir());
+        let config = config.cache_dir("/tmp/geo_rust-tests");
+        assert_eq!(
+            config.cache_location(),
+            PathBuf::from("/tmp/geo_rust-tests")
+        );
+
+        let config = config.client(reqwest::blocking::Client::new());
+        assert!(config.client.is_some());
+        assert!(config.http_client().is_ok());
+    }
+}
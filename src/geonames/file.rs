@@ -0,0 +1,157 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use crate::geonames::{load_alternate_names_data, load_gazetteer_data, try_load_postal_data};
+use crate::{AlternateName, Gazetteer, GeoError, PostalData};
+
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Read the GeoNames data in the first `.txt` file of a zip archive, skipping `readme.txt`.
+fn read_zip<R: Read + Seek>(reader: R) -> Result<String, GeoError> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let file_name = archive
+        .file_names()
+        .find(|name| {
+            let name = name.to_lowercase();
+            name.ends_with(".txt") && !name.ends_with("readme.txt")
+        })
+        .map(|name| name.to_string())
+        .ok_or_else(|| GeoError::FileNotInArchive {
+            expected: "*.txt".to_string(),
+            available: archive.file_names().map(|name| name.to_string()).collect(),
+        })?;
+
+    log::debug!("Reading {} from zip archive", file_name);
+    let mut data = String::new();
+    archive.by_name(&file_name)?.read_to_string(&mut data)?;
+    Ok(data)
+}
+
+/// Read a GeoNames data file from disk.
+///
+/// The format is detected from the file's magic bytes, so a GeoNames `.zip` archive,
+/// a gzipped `.txt.gz` file and a plain `.txt` file are all supported regardless of
+/// their extension.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+///
+/// # Returns
+///
+/// A `Result` containing the tab-separated text of the file.
+pub fn read_data_file(path: impl AsRef<Path>) -> Result<String, GeoError> {
+    let path = path.as_ref();
+    let mut file = File::open(path)?;
+    let mut magic = [0; 4];
+    let read = file.read(&mut magic)?;
+    file.rewind()?;
+
+    let mut data = String::new();
+    if magic[..read] == ZIP_MAGIC {
+        log::debug!("Reading {} as a zip archive", path.display());
+        return read_zip(BufReader::new(file));
+    } else if magic[..read.min(2)] == GZIP_MAGIC {
+        log::debug!("Reading {} as gzip", path.display());
+        GzDecoder::new(BufReader::new(file)).read_to_string(&mut data)?;
+    } else {
+        log::debug!("Reading {} as plain text", path.display());
+        file.read_to_string(&mut data)?;
+    }
+
+    Ok(data)
+}
+
+/// Load GeoNames postal data from a zip, gzip or plain text file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `PostalData` structs.
+pub fn load_postal_data_from_path(path: impl AsRef<Path>) -> Result<Vec<PostalData>, GeoError> {
+    Ok(try_load_postal_data(&read_data_file(path)?)?)
+}
+
+/// Load GeoNames gazetteer data from a zip, gzip or plain text file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `Gazetteer` structs.
+pub fn load_gazetteer_data_from_path(path: impl AsRef<Path>) -> Result<Vec<Gazetteer>, GeoError> {
+    Ok(load_gazetteer_data(&read_data_file(path)?))
+}
+
+/// Load GeoNames alternate names from a zip, gzip or plain text file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `AlternateName` structs.
+pub fn load_alternate_names_data_from_path(
+    path: impl AsRef<Path>,
+) -> Result<Vec<AlternateName>, GeoError> {
+    Ok(load_alternate_names_data(&read_data_file(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+
+    use super::*;
+
+    const DATA: &str =
+        "GB\tCM8\tWitham\tEngland\tENG\tEssex\t11E\tBraintree\t22UC\t51.8\t0.63\t6\n";
+
+    #[test_log::test]
+    fn test_read_data_file_formats() {
+        let dir = std::env::temp_dir().join(format!("geo_rust_file_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let plain = dir.join("GB.txt");
+        std::fs::write(&plain, DATA).unwrap();
+
+        let gzip = dir.join("GB.txt.gz");
+        let mut encoder = GzEncoder::new(File::create(&gzip).unwrap(), Default::default());
+        encoder.write_all(DATA.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        // the extension is deliberately wrong, as detection uses the magic bytes
+        let zip = dir.join("GB.dat");
+        let mut writer = zip::ZipWriter::new(File::create(&zip).unwrap());
+        writer
+            .start_file("readme.txt", zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(b"not data").unwrap();
+        writer
+            .start_file("GB.txt", zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(DATA.as_bytes()).unwrap();
+        writer.finish().unwrap();
+
+        for path in [&plain, &gzip, &zip] {
+            assert_eq!(read_data_file(path).unwrap(), DATA);
+        }
+        assert_eq!(
+            load_postal_data_from_path(&gzip).unwrap()[0].postal_code,
+            "CM8"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,58 +1,143 @@
-use crate::geonames::{download, Data};
+use crate::geonames::{data_lines, download, download_with_config, Data, DownloadConfig};
 use crate::models::Gazetteer;
-use crate::{Country, GeoLocation};
+use crate::{Country, GeoError, GeoLocation};
 
 const GEONAMES_GAZETTEER_URL_BASE: &str = "https://download.geonames.org/export/dump";
 
+pub(crate) const GAZETTEER_COLUMNS: [&str; 19] = [
+    "geonameid",
+    "name",
+    "asciiname",
+    "alternatenames",
+    "latitude",
+    "longitude",
+    "feature_class",
+    "feature_code",
+    "country_code",
+    "cc2",
+    "admin1_code",
+    "admin2_code",
+    "admin3_code",
+    "admin4_code",
+    "population",
+    "elevation",
+    "dem",
+    "timezone",
+    "modification_date",
+];
+
 pub(crate) fn get_gazetteer_url(country: &Country) -> String {
-    if [
-        Country::GreatBritainFull,
-        Country::UnitedKingdomFull,
-        Country::NetherlandsFull,
-        Country::CanadaFull,
-    ]
-    .contains(country)
-    {
+    if !country.supports_gazetteer() {
         panic!("Country {} not supported for gazetteer", country)
     }
 
     format!("{}/{}.zip", GEONAMES_GAZETTEER_URL_BASE, country)
 }
 
+// split a comma separated list, treating a blank field as an empty list
+fn split_list(field: &str) -> Vec<String> {
+    field
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+// yield the data rows, skipping the header row written by `write_gazetteer_csv`
+fn gazetteer_lines(data: &str) -> impl Iterator<Item = &str> {
+    data_lines(data).filter(|line| line.split('\t').next() != Some(GAZETTEER_COLUMNS[0]))
+}
+
+fn parse_gazetteer_line(line: &str) -> Gazetteer {
+    let fields: Vec<&str> = line.split('\t').collect();
+    Gazetteer {
+        id: fields[0].parse().unwrap(),
+        name: fields[1].to_string(),
+        asciiname: fields[2].to_string(),
+        alternate_names: split_list(fields[3]),
+        geolocation: Some(GeoLocation {
+            latitude: fields[4].parse().unwrap(),
+            longitude: fields[5].parse().unwrap(),
+        }),
+        feature_class: fields[6].to_string(),
+        feature_code: fields[7].to_string(),
+        country_code: fields[8].to_string(),
+        alternate_country_codes: split_list(fields[9]),
+        admin1_code: fields.get(10).map(|s| s.to_string()),
+        admin2_code: fields.get(11).map(|s| s.to_string()),
+        admin3_code: fields.get(12).map(|s| s.to_string()),
+        admin4_code: fields.get(13).map(|s| s.to_string()),
+        population: fields[14].parse().unwrap_or_default(),
+        elevation: fields[15].parse().unwrap_or_default(),
+        dem: fields[16].parse().unwrap(),
+        timezone: fields[17].to_string(),
+        modification_date: chrono::NaiveDate::parse_from_str(fields[18], "%Y-%m-%d").unwrap(),
+    }
+}
+
+/// Parse GeoNames gazetteer data.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames gazetteer records.
+///
+/// # Returns
+///
+/// A `Vec` of `Gazetteer` structs.
 pub fn load_gazetteer_data(data: &str) -> Vec<Gazetteer> {
     log::debug!("Parsing geonames data");
-    let data: Vec<Gazetteer> = data
-        .lines()
-        .map(|line| {
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "parse",
+        data_type = "gazetteer",
+        records = tracing::field::Empty
+    )
+    .entered();
+    let data: Vec<Gazetteer> = gazetteer_lines(data).map(parse_gazetteer_line).collect();
+
+    log::debug!("Parsed {} records", data.len());
+    #[cfg(feature = "tracing")]
+    span.record("records", data.len());
+
+    data
+}
+
+/// Parse GeoNames gazetteer data, keeping only large enough places of some feature classes.
+///
+/// Rows are filtered on their raw fields before they are parsed, so discarded rows are
+/// never allocated.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames gazetteer records.
+/// * `min_population` - An `i64` representing the minimum population to keep.
+/// * `classes` - The feature classes to keep, e.g. `&["P"]` for populated places, or an
+///   empty slice to keep every class.
+///
+/// # Returns
+///
+/// A `Vec` of the matching `Gazetteer` structs.
+pub fn load_gazetteer_data_filtered(
+    data: &str,
+    min_population: i64,
+    classes: &[&str],
+) -> Vec<Gazetteer> {
+    let data: Vec<Gazetteer> = gazetteer_lines(data)
+        .filter(|line| {
             let fields: Vec<&str> = line.split('\t').collect();
-            Gazetteer {
-                id: fields[0].parse().unwrap(),
-                name: fields[1].to_string(),
-                asciiname: fields[2].to_string(),
-                alternate_names: fields[3].split(',').map(|s| s.to_string()).collect(),
-                geolocation: Some(GeoLocation {
-                    latitude: fields[4].parse().unwrap(),
-                    longitude: fields[5].parse().unwrap(),
-                }),
-                feature_class: fields[6].to_string(),
-                feature_code: fields[7].to_string(),
-                country_code: fields[8].to_string(),
-                alternate_country_codes: fields[9].split(',').map(|s| s.to_string()).collect(),
-                admin1_code: fields.get(10).map(|s| s.to_string()),
-                admin2_code: fields.get(11).map(|s| s.to_string()),
-                admin3_code: fields.get(12).map(|s| s.to_string()),
-                admin4_code: fields.get(13).map(|s| s.to_string()),
-                population: fields[14].parse().unwrap_or_default(),
-                elevation: fields[15].parse().unwrap_or_default(),
-                dem: fields[16].parse().unwrap(),
-                timezone: fields[17].to_string(),
-                modification_date: chrono::NaiveDate::parse_from_str(fields[18], "%Y-%m-%d")
-                    .unwrap(),
-            }
+            let class = fields.get(6).copied().unwrap_or_default();
+            let population: i64 = fields
+                .get(14)
+                .and_then(|population| population.parse().ok())
+                .unwrap_or_default();
+
+            population >= min_population && (classes.is_empty() || classes.contains(&class))
         })
+        .map(parse_gazetteer_line)
         .collect();
 
-    log::debug!("Parsed {} records", data.len());
+    log::debug!("Kept {} records", data.len());
 
     data
 }
@@ -71,6 +156,49 @@ pub fn get_gazetteer_data(country: Country) -> Vec<Gazetteer> {
     load_gazetteer_data(&data)
 }
 
+/// Get Gazetteer data for a specific country, keeping only large enough places of some
+/// feature classes.
+///
+/// The whole file is downloaded, but only the matching rows are parsed and kept, which
+/// saves a lot of memory as most gazetteer rows are small features.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `min_population` - An `i64` representing the minimum population to keep.
+/// * `classes` - The feature classes to keep, e.g. `&["P"]` for populated places, or an
+///   empty slice to keep every class.
+///
+/// # Returns
+///
+/// A `Vec` of the matching `Gazetteer` structs.
+pub fn get_gazetteer_data_filtered(
+    country: Country,
+    min_population: i64,
+    classes: &[&str],
+) -> Vec<Gazetteer> {
+    let data = download(&country, Data::Gazetteer).unwrap();
+    load_gazetteer_data_filtered(&data, min_population, classes)
+}
+
+/// Get Gazetteer data for a specific country using a custom configuration.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `config` - A `DownloadConfig` controlling how the data is fetched.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `Gazetteer` structs.
+pub fn get_gazetteer_data_with_config(
+    country: Country,
+    config: &DownloadConfig,
+) -> Result<Vec<Gazetteer>, GeoError> {
+    let data = download_with_config(&country, Data::Gazetteer, config)?;
+    Ok(load_gazetteer_data(&data))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +208,47 @@ mod tests {
         let gazetteer_data = get_gazetteer_data(Country::UnitedKingdom);
         assert!(!gazetteer_data.is_empty());
     }
+
+    #[test_log::test]
+    fn test_load_gazetteer_without_alternate_names() {
+        let data = load_gazetteer_data(
+            "2634633\tWitham\tWitham\t\t51.80007\t0.64038\tP\tPPL\tGB\t\tENG\t11\t22UC\t\t25353\t0\t30\tEurope/London\t2018-07-03\n\
+             2643743\tLondon\tLondon\t Londres , Londra \t51.50853\t-0.12574\tP\tPPLC\tGB\t\tENG\tGLA\t\t\t8961989\t0\t25\tEurope/London\t2024-02-19\n",
+        );
+
+        assert!(data[0].alternate_names.is_empty());
+        assert!(data[0].alternate_country_codes.is_empty());
+        assert_eq!(data[1].alternate_names, ["Londres", "Londra"]);
+    }
+
+    #[test_log::test]
+    fn test_load_gazetteer_data_filtered() {
+        let data = "2634633\tWitham\tWitham\t\t51.80007\t0.64038\tP\tPPL\tGB\t\tENG\t11\t22UC\t\t25353\t0\t30\tEurope/London\t2018-07-03\n\
+                    2633873\tWoodham Mortimer\tWoodham Mortimer\t\t51.71\t0.61\tP\tPPL\tGB\t\tENG\t11\t\t\t0\t0\t40\tEurope/London\t2018-07-03\n\
+                    2649889\tEssex\tEssex\t\t51.75\t0.58\tA\tADM2\tGB\t\tENG\t11\t\t\t1393600\t0\t40\tEurope/London\t2018-07-03\n";
+
+        let names = |min_population, classes: &[&str]| -> Vec<String> {
+            load_gazetteer_data_filtered(data, min_population, classes)
+                .into_iter()
+                .map(|geoname| geoname.name)
+                .collect()
+        };
+        assert_eq!(names(1000, &["P"]), ["Witham"]);
+        assert_eq!(names(1000, &[]), ["Witham", "Essex"]);
+        assert_eq!(names(0, &["P"]), ["Witham", "Woodham Mortimer"]);
+        assert!(names(10_000_000, &[]).is_empty());
+    }
+
+    #[test_log::test]
+    fn test_load_gazetteer_bom_and_crlf() {
+        let data = load_gazetteer_data(
+            "\u{feff}2634633\tWitham\tWitham\t\t51.80007\t0.64038\tP\tPPL\tGB\t\tENG\t11\t22UC\t\t25353\t0\t30\tEurope/London\t2018-07-03\r\n",
+        );
+
+        assert_eq!(data[0].id, 2634633);
+        assert_eq!(
+            data[0].modification_date,
+            chrono::NaiveDate::from_ymd_opt(2018, 7, 3).unwrap()
+        );
+    }
 }
@@ -0,0 +1,321 @@
+use std::fmt::Display;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::geonames::cache::{cache_dir, cache_path_in, list_cached_countries_in};
+use crate::geonames::{Data, DownloadConfig};
+use crate::{Country, GeoError, ParseError};
+
+/// The name of the manifest file written to the cache directory by `cache_manifest`.
+pub const MANIFEST_FILE_NAME: &str = "manifest.tsv";
+
+/// A fingerprint of a single cached GeoNames file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    pub country: Country,
+    pub data_type: Data,
+    // size of the cached file in bytes
+    pub size: u64,
+    // 64-bit FNV-1a hash of the cached file
+    pub hash: u64,
+}
+
+/// A fingerprint of every cached GeoNames file, used to pin a dataset version.
+///
+/// The manifest is written as tab-separated text, one file per line, and can be read back
+/// with `str::parse`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheManifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+fn data_type_name(data_type: Data) -> &'static str {
+    match data_type {
+        Data::Postal => "postal",
+        Data::Gazetteer => "gazetteer",
+        Data::AlternateNames => "alternate_names",
+    }
+}
+
+fn data_type_from_name(name: &str) -> Option<Data> {
+    match name {
+        "postal" => Some(Data::Postal),
+        "gazetteer" => Some(Data::Gazetteer),
+        "alternate_names" => Some(Data::AlternateNames),
+        _ => None,
+    }
+}
+
+/// Hash a file with 64-bit FNV-1a, which is stable across platforms and Rust versions.
+fn hash_file(path: &Path) -> std::io::Result<u64> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let mut buffer = [0; 64 * 1024];
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hash);
+        }
+        for byte in &buffer[..read] {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+fn manifest_entry(
+    root: &Path,
+    country: Country,
+    data_type: Data,
+) -> std::io::Result<ManifestEntry> {
+    let path = cache_path_in(root, &country, data_type);
+    Ok(ManifestEntry {
+        size: std::fs::metadata(&path)?.len(),
+        hash: hash_file(&path)?,
+        country,
+        data_type,
+    })
+}
+
+/// Record a fingerprint of the current cache.
+///
+/// Every cached file is hashed and the manifest is written to `manifest.tsv` in the cache
+/// directory. Keep a copy of the manifest and pass it to `require_version` to check that a
+/// later run uses exactly the same data.
+///
+/// # Returns
+///
+/// A `Result` containing the `CacheManifest` describing the cached files.
+pub fn cache_manifest() -> Result<CacheManifest, GeoError> {
+    cache_manifest_in(&cache_dir())
+}
+
+/// Record a fingerprint of the cache directory set in a `DownloadConfig`.
+///
+/// # Arguments
+///
+/// * `config` - A `DownloadConfig` whose `cache_location` is fingerprinted.
+///
+/// # Returns
+///
+/// A `Result` containing the `CacheManifest` describing the cached files.
+pub fn cache_manifest_with_config(config: &DownloadConfig) -> Result<CacheManifest, GeoError> {
+    cache_manifest_in(&config.cache_location())
+}
+
+fn cache_manifest_in(root: &Path) -> Result<CacheManifest, GeoError> {
+    let mut manifest = CacheManifest::default();
+    for data_type in [Data::Postal, Data::Gazetteer, Data::AlternateNames] {
+        for country in list_cached_countries_in(root, data_type) {
+            manifest
+                .entries
+                .push(manifest_entry(root, country, data_type)?);
+        }
+    }
+
+    std::fs::create_dir_all(root)?;
+    std::fs::write(root.join(MANIFEST_FILE_NAME), manifest.to_string())?;
+    log::debug!(
+        "Recorded {} cached files in manifest",
+        manifest.entries.len()
+    );
+
+    Ok(manifest)
+}
+
+/// Check that the cache matches a previously recorded manifest.
+///
+/// Only the files listed in the manifest are checked, so countries cached since it was
+/// recorded do not cause an error.
+///
+/// # Arguments
+///
+/// * `manifest` - A `CacheManifest` struct, as returned by `cache_manifest`.
+///
+/// # Returns
+///
+/// A `Result` which is a `GeoError::CacheMismatch` containing the path of the first cached
+/// file that is missing or has changed.
+pub fn require_version(manifest: &CacheManifest) -> Result<(), GeoError> {
+    require_version_in(&cache_dir(), manifest)
+}
+
+/// Check that the cache directory set in a `DownloadConfig` matches a previously recorded
+/// manifest.
+///
+/// # Arguments
+///
+/// * `manifest` - A `CacheManifest` struct, as returned by `cache_manifest_with_config`.
+/// * `config` - A `DownloadConfig` whose `cache_location` is checked.
+///
+/// # Returns
+///
+/// A `Result` which is a `GeoError::CacheMismatch` containing the path of the first cached
+/// file that is missing or has changed.
+pub fn require_version_with_config(
+    manifest: &CacheManifest,
+    config: &DownloadConfig,
+) -> Result<(), GeoError> {
+    require_version_in(&config.cache_location(), manifest)
+}
+
+fn require_version_in(root: &Path, manifest: &CacheManifest) -> Result<(), GeoError> {
+    for entry in &manifest.entries {
+        let path = cache_path_in(root, &entry.country, entry.data_type);
+        let matches = match manifest_entry(root, entry.country.clone(), entry.data_type) {
+            Ok(current) => current == *entry,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e.into()),
+        };
+
+        if !matches {
+            log::warn!("Cached file {} does not match the manifest", path.display());
+            return Err(GeoError::CacheMismatch(path));
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for CacheManifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{:016x}",
+                data_type_name(entry.data_type),
+                entry.country,
+                entry.size,
+                entry.hash
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CacheManifest {
+    type Err = GeoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut manifest = CacheManifest::default();
+        for (index, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let line_number = index + 1;
+            let fields: Vec<&str> = line.split('\t').collect();
+            let field = |index: usize| {
+                fields.get(index).copied().ok_or(ParseError::MissingField {
+                    line: line_number,
+                    index,
+                })
+            };
+            let invalid = |index: usize, value: &str| ParseError::InvalidField {
+                line: line_number,
+                index,
+                value: value.to_string(),
+            };
+
+            let data_type = field(0)?;
+            let country = field(1)?;
+            let size = field(2)?;
+            let hash = field(3)?;
+            manifest.entries.push(ManifestEntry {
+                data_type: data_type_from_name(data_type).ok_or_else(|| invalid(0, data_type))?,
+                country: Country::from_str(country).map_err(|_| invalid(1, country))?,
+                size: size.parse().map_err(|_| invalid(2, size))?,
+                hash: u64::from_str_radix(hash, 16).map_err(|_| invalid(3, hash))?,
+            });
+        }
+
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_manifest_round_trip() {
+        let manifest = CacheManifest {
+            entries: vec![
+                ManifestEntry {
+                    country: Country::France,
+                    data_type: Data::Postal,
+                    size: 1024,
+                    hash: 0xdeadbeef,
+                },
+                ManifestEntry {
+                    country: Country::Andorra,
+                    data_type: Data::AlternateNames,
+                    size: 0,
+                    hash: u64::MAX,
+                },
+            ],
+        };
+
+        let text = manifest.to_string();
+        assert_eq!(text.parse::<CacheManifest>().unwrap(), manifest);
+        assert!(matches!(
+            "postal\tGB".parse::<CacheManifest>(),
+            Err(GeoError::Parse(ParseError::MissingField {
+                line: 1,
+                index: 2
+            }))
+        ));
+    }
+
+    #[test_log::test]
+    fn test_custom_cache_dir() {
+        let root = std::env::temp_dir().join(format!("geo_rust_manifest_{}", std::process::id()));
+        let config = DownloadConfig::new().cache_dir(&root);
+        let cache_path = cache_path_in(&root, &Country::Andorra, Data::Postal);
+        std::fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+        std::fs::write(&cache_path, "AD\tAD100\n").unwrap();
+
+        assert_eq!(
+            crate::list_cached_countries_with_config(Data::Postal, &config),
+            [Country::Andorra]
+        );
+        assert!(crate::cache_size_bytes_with_config(&config).unwrap() > 0);
+
+        let manifest = cache_manifest_with_config(&config).unwrap();
+        assert_eq!(manifest.entries.len(), 1);
+        assert!(root.join(MANIFEST_FILE_NAME).exists());
+        require_version_with_config(&manifest, &config).unwrap();
+
+        crate::invalidate_country_with_config(&Country::Andorra, Data::Postal, &config).unwrap();
+        assert!(matches!(
+            require_version_with_config(&manifest, &config),
+            Err(GeoError::CacheMismatch(_))
+        ));
+
+        crate::invalidate_cache_with_config(&config).unwrap();
+        assert!(!root.join(MANIFEST_FILE_NAME).exists());
+        assert_eq!(crate::cache_size_bytes_with_config(&config).unwrap(), 0);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_require_version_missing_file() {
+        assert!(require_version(&CacheManifest::default()).is_ok());
+
+        let manifest = CacheManifest {
+            entries: vec![ManifestEntry {
+                country: Country::VaticanCity,
+                data_type: Data::Gazetteer,
+                size: 0,
+                hash: 0,
+            }],
+        };
+        assert!(matches!(
+            require_version(&manifest),
+            Err(GeoError::CacheMismatch(_))
+        ));
+    }
+}
@@ -1,17 +1,96 @@
 use std::env::temp_dir;
+use std::fs::File;
 use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-pub use gazetteer::get_gazetteer_data;
-pub use postal::get_postal_data;
+pub use alternate_names::{
+    get_alternate_names_data, get_alternate_names_data_with_config, load_alternate_names_data,
+};
+pub use cache::{
+    cache_dir, cache_size_bytes, cache_size_bytes_with_config, invalidate_cache,
+    invalidate_cache_with_config, invalidate_country, invalidate_country_with_config,
+    list_cached_countries, list_cached_countries_with_config, warm_cache, warm_cache_with_config,
+};
+pub use config::{DownloadConfig, DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT};
+pub use file::{
+    load_alternate_names_data_from_path, load_gazetteer_data_from_path, load_postal_data_from_path,
+    read_data_file,
+};
+#[cfg(feature = "csv")]
+pub(crate) use gazetteer::GAZETTEER_COLUMNS;
+pub use gazetteer::{
+    get_gazetteer_data, get_gazetteer_data_filtered, get_gazetteer_data_with_config,
+    load_gazetteer_data, load_gazetteer_data_filtered,
+};
+pub use manifest::{
+    cache_manifest, cache_manifest_with_config, require_version, require_version_with_config,
+    CacheManifest, ManifestEntry, MANIFEST_FILE_NAME,
+};
+#[cfg(feature = "csv")]
+pub(crate) use postal::POSTAL_COLUMNS;
+pub use postal::{
+    dedup_by_postal_code, for_each_postal_record, for_each_postal_record_from_reader,
+    get_postal_data, get_postal_data_checked, get_postal_data_with_config, get_postal_summaries,
+    load_postal_data, load_postal_data_dedup, load_postal_data_with_schema, load_postal_summaries,
+    parse_postal_line, parse_postal_line_with_schema, stream_all_countries_postal,
+    stream_all_countries_postal_with_config, try_load_postal_data,
+    try_load_postal_data_with_schema, try_load_postal_summaries,
+};
+pub use schema::PostalSchema;
 
-use crate::Country;
+use crate::{Country, GeoError};
 
+mod alternate_names;
+mod cache;
+mod config;
+mod file;
 mod gazetteer;
+mod manifest;
 mod postal;
+mod schema;
 
+/// The type of GeoNames dataset.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Data {
     Postal,
     Gazetteer,
+    AlternateNames,
+}
+
+/// Where the data returned by a download came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadResult {
+    // the data was read from the cache
+    CacheHit,
+    // the data was downloaded from GeoNames, with the size of the archive in bytes
+    Downloaded(u64),
+}
+
+/// The UTF-8 byte order mark some mirrors and Windows editors add to the start of a file.
+pub(crate) const BOM: char = '\u{feff}';
+
+/// Split data into lines, ignoring a leading byte order mark and `\r\n` line endings.
+pub(crate) fn data_lines(data: &str) -> impl Iterator<Item = &str> {
+    data.strip_prefix(BOM)
+        .unwrap_or(data)
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+}
+
+/// Open a file in a zip archive, listing the archive's contents if it is missing.
+pub(crate) fn archive_file<'a, R: Read + std::io::Seek>(
+    archive: &'a mut zip::ZipArchive<R>,
+    file_name: &str,
+) -> Result<zip::read::ZipFile<'a>, GeoError> {
+    if !archive.file_names().any(|name| name == file_name) {
+        return Err(GeoError::FileNotInArchive {
+            expected: file_name.to_string(),
+            available: archive.file_names().map(|name| name.to_string()).collect(),
+        });
+    }
+
+    Ok(archive.by_name(file_name)?)
 }
 
 pub fn get_temp_dir() -> String {
@@ -26,76 +105,360 @@ pub fn get_os_separator() -> String {
     std::path::MAIN_SEPARATOR.to_string()
 }
 
-/// Invalidate the cache.
+/// Get the raw GeoNames data for a country without parsing it.
+///
+/// The data is read from the cache if available, otherwise it is downloaded and cached.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `data_type` - A `Data` enum representing the type of data.
+///
+/// # Returns
+///
+/// A `Result` containing the tab-separated text exactly as published by GeoNames.
+pub fn fetch_raw(country: &Country, data_type: Data) -> Result<String, GeoError> {
+    download(country, data_type)
+}
+
+/// Get the raw GeoNames data for a country without parsing it, using a custom configuration.
+///
+/// # Arguments
 ///
-/// This function will remove any cached data that has been downloaded.
-pub fn invalidate_cache() {
-    let cache_dir = std::env::var("GEOCODER_CACHE_DIR").unwrap_or(get_temp_dir());
+/// * `country` - A `Country` enum representing the country.
+/// * `data_type` - A `Data` enum representing the type of data.
+/// * `config` - A `DownloadConfig` controlling how the data is fetched.
+///
+/// # Returns
+///
+/// A `Result` containing the tab-separated text exactly as published by GeoNames.
+pub fn fetch_raw_with_config(
+    country: &Country,
+    data_type: Data,
+    config: &DownloadConfig,
+) -> Result<String, GeoError> {
+    download_with_config(country, data_type, config)
+}
 
-    let postal_cache = format!("{}{}postal", cache_dir, get_os_separator());
-    let gazetteer_cache = format!("{}{}gazetteer", cache_dir, get_os_separator());
+/// Get the raw GeoNames data for a country, along with whether it came from the cache.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `data_type` - A `Data` enum representing the type of data.
+/// * `config` - A `DownloadConfig` controlling how the data is fetched.
+///
+/// # Returns
+///
+/// A `Result` containing the tab-separated text and a `LoadResult` describing where it came from.
+pub fn fetch_raw_with_status(
+    country: &Country,
+    data_type: Data,
+    config: &DownloadConfig,
+) -> Result<(String, LoadResult), GeoError> {
+    download_with_status(country, data_type, config)
+}
 
-    if std::path::Path::new(&postal_cache).exists() {
-        log::debug!("Removing postal cache");
-        std::fs::remove_dir_all(postal_cache).unwrap();
+fn data_url(country: &Country, data_type: Data) -> String {
+    match data_type {
+        Data::Postal => postal::get_postal_url(country),
+        Data::Gazetteer => gazetteer::get_gazetteer_url(country),
+        Data::AlternateNames => alternate_names::get_alternate_names_url(country),
     }
+}
 
-    if std::path::Path::new(&gazetteer_cache).exists() {
-        log::debug!("Removing gazetteer cache");
-        std::fs::remove_dir_all(gazetteer_cache).unwrap();
+/// Check whether a GeoNames file exists without downloading it.
+///
+/// A `HEAD` request is sent, so only the headers are transferred. In offline mode no
+/// request is made and the size of the cached file is returned instead.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `data_type` - A `Data` enum representing the type of data.
+///
+/// # Returns
+///
+/// A `Result` containing the size of the file in bytes, or `None` if GeoNames does not
+/// publish it. If the server does not report a valid `Content-Length`, the file exists but
+/// its size is unknown, and `Some(0)` is returned.
+pub fn check_available(country: &Country, data_type: Data) -> Result<Option<u64>, GeoError> {
+    check_available_with_config(country, data_type, &DownloadConfig::default())
+}
+
+/// Check whether a GeoNames file exists without downloading it, using a custom configuration.
+///
+/// The request uses the configured client, timeouts and user agent, and offline mode looks
+/// for the file in the configured cache directory.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `data_type` - A `Data` enum representing the type of data.
+/// * `config` - A `DownloadConfig` controlling how the request is made.
+///
+/// # Returns
+///
+/// A `Result` containing the size of the file in bytes, or `None` if GeoNames does not
+/// publish it. If the server does not report a valid `Content-Length`, the file exists but
+/// its size is unknown, and `Some(0)` is returned.
+pub fn check_available_with_config(
+    country: &Country,
+    data_type: Data,
+    config: &DownloadConfig,
+) -> Result<Option<u64>, GeoError> {
+    if data_type != Data::Postal && !country.supports_gazetteer() {
+        return Ok(None);
+    }
+
+    if config.is_offline() {
+        let cache_path = cache::cache_path_in(&config.cache_location(), country, data_type);
+        return match std::fs::metadata(&cache_path) {
+            Ok(metadata) => Ok(Some(metadata.len())),
+            Err(_) => Err(GeoError::OfflineCacheMiss(cache_path)),
+        };
     }
+
+    let url = data_url(country, data_type);
+    log::debug!("Checking availability of {}", url);
+    let response = config.http_client()?.head(&url).send()?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let response = response.error_for_status()?;
+
+    // read the header directly, as the body of a HEAD response is always empty
+    let size = response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok()?.parse().ok())
+        .unwrap_or_default();
+
+    Ok(Some(size))
+}
+
+pub fn download(country: &Country, data_type: Data) -> Result<String, GeoError> {
+    download_with_config(country, data_type, &DownloadConfig::default())
 }
 
-pub fn download(country: &Country, data_type: Data) -> Result<String, Box<dyn std::error::Error>> {
-    let disable_cache = std::env::var("DISABLE_GEOCODER_CACHE").is_ok();
-    let cache_dir = std::env::var("GEOCODER_CACHE_DIR").unwrap_or(get_temp_dir());
+pub(crate) fn download_with_config(
+    country: &Country,
+    data_type: Data,
+    config: &DownloadConfig,
+) -> Result<String, GeoError> {
+    let (data, _) = download_with_status(country, data_type, config)?;
+    Ok(data)
+}
 
+pub(crate) fn download_with_status(
+    country: &Country,
+    data_type: Data,
+    config: &DownloadConfig,
+) -> Result<(String, LoadResult), GeoError> {
+    let (data_file, result) = download_file_with_status(country, data_type, config)?;
+    let mut data = String::new();
+    File::open(data_file.path())?.read_to_string(&mut data)?;
+    #[cfg(feature = "tracing")]
+    if result == LoadResult::CacheHit {
+        tracing::debug!(bytes = data.len(), "cache hit");
+    }
+
+    Ok((data, result))
+}
+
+/// A data file extracted from a GeoNames archive.
+///
+/// The file is deleted when this is dropped if it was extracted to a temporary path because
+/// caching is disabled.
+pub(crate) struct DataFile {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl DataFile {
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DataFile {
+    fn drop(&mut self) {
+        if self.temporary {
+            if let Err(error) = std::fs::remove_file(&self.path) {
+                log::warn!("Failed to remove {}: {}", self.path.display(), error);
+            }
+        }
+    }
+}
+
+/// Get a unique temporary path to extract data to when caching is disabled.
+fn temporary_path(country: &Country, data_type: Data) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    temp_dir().join(format!(
+        "geo_rust-{}-{:?}-{}-{}.txt",
+        country,
+        data_type,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Get the path of the extracted data file for a country, downloading it if needed.
+///
+/// The file is the cached copy unless `DISABLE_GEOCODER_CACHE` is set, in which case the
+/// archive is extracted to a temporary file that is deleted when the `DataFile` is dropped.
+pub(crate) fn download_file_with_status(
+    country: &Country,
+    data_type: Data,
+    config: &DownloadConfig,
+) -> Result<(DataFile, LoadResult), GeoError> {
+    let disable_cache = std::env::var("DISABLE_GEOCODER_CACHE").is_ok() && !config.is_offline();
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("download", %country, ?data_type).entered();
+
+    let root = config.cache_location();
     log::debug!(
-        "Cache dir: {} | Disable cache: {}",
-        cache_dir,
-        disable_cache
+        "Cache dir: {} | Disable cache: {} | Offline: {}",
+        root.display(),
+        disable_cache,
+        config.is_offline()
     );
 
-    let url = match data_type {
-        Data::Postal => postal::get_postal_url(country),
-        Data::Gazetteer => gazetteer::get_gazetteer_url(country),
-    };
-    let cache_dir = match data_type {
-        Data::Postal => format!("{}{}postal", cache_dir, get_os_separator()),
-        Data::Gazetteer => format!("{}{}gazetteer", cache_dir, get_os_separator()),
+    let url = data_url(country, data_type);
+    let cache_dir = cache::data_cache_dir_in(&root, data_type);
+    let cache_path = cache::cache_path_in(&root, country, data_type);
+
+    let fresh = match config.max_age_for(data_type) {
+        Some(max_age) if !config.is_offline() => cache::is_fresh(&cache_path, max_age),
+        _ => true,
     };
-    let cache_path = format!("{}{}{}.txt", cache_dir, get_os_separator(), country);
 
-    if !disable_cache && std::path::Path::new(&cache_path).exists() {
-        log::debug!("Using cached data from {}", cache_path);
-        let mut data = String::new();
-        std::fs::File::open(cache_path)?.read_to_string(&mut data)?;
-        return Ok(data);
+    if !disable_cache && cache_path.exists() && fresh {
+        log::debug!("Using cached data from {}", cache_path.display());
+        let data_file = DataFile {
+            path: cache_path,
+            temporary: false,
+        };
+        return Ok((data_file, LoadResult::CacheHit));
     }
 
-    log::info!("Downloading data from {}", url);
-    let response = reqwest::blocking::get(url)?;
-    let zip_file = response.bytes()?;
-    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip_file))?;
-
-    let file_name = format!("{}.txt", country);
-    let mut data_file = archive.by_name(&file_name)?;
-    let mut data = String::new();
-    data_file.read_to_string(&mut data)?;
+    if config.is_offline() {
+        return Err(GeoError::OfflineCacheMiss(cache_path));
+    }
 
-    if !disable_cache {
-        log::debug!("Caching data to {}", cache_dir);
+    let data_file = if disable_cache {
+        DataFile {
+            path: temporary_path(country, data_type),
+            temporary: true,
+        }
+    } else {
+        log::debug!("Caching data to {}", cache_dir.display());
         std::fs::create_dir_all(cache_dir)?;
-        std::fs::write(cache_path, &data)?;
+        DataFile {
+            path: cache_path,
+            temporary: false,
+        }
+    };
+
+    log::info!("Downloading data from {}", url);
+    let zip_path = data_file.path().with_extension("zip");
+    let mut response = config.http_client()?.get(&url).send()?.error_for_status()?;
+    let downloaded = response.copy_to(&mut File::create(&zip_path)?)?;
+    #[cfg(feature = "tracing")]
+    tracing::info!(%url, bytes = downloaded, "downloaded");
+
+    let file_name = match data_type {
+        Data::AlternateNames => alternate_names::get_alternate_names_file_name(country),
+        _ => format!("{}.txt", country),
+    };
+    let extracted = extract_file(&zip_path, &file_name, data_file.path());
+    std::fs::remove_file(&zip_path)?;
+    if extracted.is_err() && !data_file.temporary {
+        // don't leave a partial file behind to be read as a cache hit
+        let _ = std::fs::remove_file(data_file.path());
     }
+    extracted?;
 
-    Ok(data)
+    Ok((data_file, LoadResult::Downloaded(downloaded)))
+}
+
+/// Extract a file from a zip archive on disk to `destination`.
+fn extract_file(zip_path: &Path, file_name: &str, destination: &Path) -> Result<(), GeoError> {
+    log::debug!("Extracting {} to {}", file_name, destination.display());
+    let mut archive = zip::ZipArchive::new(File::open(zip_path)?)?;
+    let mut data_file = archive_file(&mut archive, file_name)?;
+    std::io::copy(&mut data_file, &mut File::create(destination)?)?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test_log::test]
+    fn test_offline_cache_miss() {
+        let root = std::env::temp_dir().join("geo_rust-test-offline-cache-miss");
+        std::fs::create_dir_all(&root).unwrap();
+
+        let config = DownloadConfig::new().offline(true).cache_dir(&root);
+        let result = download_with_config(&Country::WallisAndFutuna, Data::Gazetteer, &config);
+        assert!(matches!(result, Err(GeoError::OfflineCacheMiss(_))));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_custom_cache_dir() {
+        let root = std::env::temp_dir().join("geo_rust-test-custom-cache-dir");
+        let cache_path = cache::cache_path_in(&root, &Country::Andorra, Data::Postal);
+        std::fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+        std::fs::write(&cache_path, "AD\tAD100\tCanillo\n").unwrap();
+
+        let config = DownloadConfig::new().offline(true).cache_dir(&root);
+        let (data, result) =
+            download_with_status(&Country::Andorra, Data::Postal, &config).unwrap();
+        assert_eq!(data, "AD\tAD100\tCanillo\n");
+        assert_eq!(result, LoadResult::CacheHit);
+        assert_eq!(
+            check_available_with_config(&Country::Andorra, Data::Postal, &config).unwrap(),
+            Some(17)
+        );
+        assert!(matches!(
+            check_available_with_config(&Country::Monaco, Data::Postal, &config),
+            Err(GeoError::OfflineCacheMiss(_))
+        ));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_check_available_unsupported() {
+        assert_eq!(
+            check_available(&Country::GreatBritainFull, Data::Gazetteer).unwrap(),
+            None
+        );
+    }
+
+    #[test_log::test]
+    fn test_archive_file_not_found() {
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file("readme.txt", zip::write::FileOptions::default())
+            .unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let mut archive = zip::ZipArchive::new(buffer).unwrap();
+        assert!(archive_file(&mut archive, "readme.txt").is_ok());
+        let error = archive_file(&mut archive, "GB.txt").err().unwrap();
+        assert!(matches!(
+            error,
+            GeoError::FileNotInArchive { ref expected, ref available }
+                if expected == "GB.txt" && available == &["readme.txt"]
+        ));
+    }
+
     #[test_log::test]
     fn test_download_postal() {
         let data = download(&Country::UnitedStates, Data::Postal).unwrap();
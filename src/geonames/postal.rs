@@ -1,8 +1,31 @@
-use crate::geonames::{download, Data};
-use crate::{Country, GeoLocation, PostalData};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::geonames::{
+    data_lines, download, download_file_with_status, download_with_config, Data, DownloadConfig,
+    PostalSchema, BOM,
+};
+use crate::haversine::spherical_centroid;
+use crate::{Accuracy, Country, GeoError, GeoLocation, ParseError, PostalData, PostalSummary};
 
 const GENONAMES_POSTAL_URL_BASE: &str = "http://download.geonames.org/export/zip";
 
+pub(crate) const POSTAL_COLUMNS: [&str; 12] = [
+    "country_code",
+    "postal_code",
+    "place_name",
+    "admin_name1",
+    "admin_code1",
+    "admin_name2",
+    "admin_code2",
+    "admin_name3",
+    "admin_code3",
+    "latitude",
+    "longitude",
+    "accuracy",
+];
+
 pub(crate) fn get_postal_url(country: &Country) -> String {
     if [
         Country::GreatBritainFull,
@@ -18,37 +41,465 @@ pub(crate) fn get_postal_url(country: &Country) -> String {
     format!("{}/{}.zip", GENONAMES_POSTAL_URL_BASE, country)
 }
 
+fn is_header(line: &str, schema: &PostalSchema) -> bool {
+    // the header row written by `write_postal_csv`
+    line.split(schema.delimiter).nth(schema.country_code) == Some(POSTAL_COLUMNS[0])
+}
+
+fn check_column_count(
+    line: &str,
+    line_number: usize,
+    schema: &PostalSchema,
+) -> Result<(), ParseError> {
+    let found = line.split(schema.delimiter).count();
+    let expected = schema.column_count();
+    if found < expected {
+        return Err(ParseError::UnexpectedColumnCount {
+            line: line_number,
+            found,
+            expected,
+        });
+    }
+
+    Ok(())
+}
+
+fn optional(fields: &[&str], index: Option<usize>) -> Option<String> {
+    fields.get(index?).map(|s| s.to_string())
+}
+
+fn required<'a>(fields: &[&'a str], index: usize, line: usize) -> Result<&'a str, ParseError> {
+    fields
+        .get(index)
+        .copied()
+        .ok_or(ParseError::MissingField { line, index })
+}
+
+fn parse_coordinate(fields: &[&str], index: usize, line: usize) -> Result<f64, ParseError> {
+    let value = required(fields, index, line)?;
+    value.parse().map_err(|_| ParseError::InvalidField {
+        line,
+        index,
+        value: value.to_string(),
+    })
+}
+
+/// Parse a single line of GeoNames postal data.
+///
+/// # Arguments
+///
+/// * `line` - A `&str` containing one tab-separated GeoNames postal record.
+/// * `line_number` - The 1-based line number, used in error messages.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `PostalData` struct.
+pub fn parse_postal_line(line: &str, line_number: usize) -> Result<PostalData, ParseError> {
+    parse_postal_line_with_schema(line, line_number, &PostalSchema::GEONAMES)
+}
+
+/// Parse a single line of postal data with a custom layout.
+///
+/// # Arguments
+///
+/// * `line` - A `&str` containing one delimited postal record.
+/// * `line_number` - The 1-based line number, used in error messages.
+/// * `schema` - A `PostalSchema` describing the delimiter and columns.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `PostalData` struct.
+pub fn parse_postal_line_with_schema(
+    line: &str,
+    line_number: usize,
+    schema: &PostalSchema,
+) -> Result<PostalData, ParseError> {
+    let fields: Vec<&str> = line.split(schema.delimiter).collect();
+
+    Ok(PostalData {
+        country_code: required(&fields, schema.country_code, line_number)?.to_string(),
+        postal_code: required(&fields, schema.postal_code, line_number)?.to_string(),
+        place_name: optional(&fields, schema.place_name),
+        admin_name1: optional(&fields, schema.admin_name1),
+        admin_code1: optional(&fields, schema.admin_code1),
+        admin_name2: optional(&fields, schema.admin_name2),
+        admin_code2: optional(&fields, schema.admin_code2),
+        admin_name3: optional(&fields, schema.admin_name3),
+        admin_code3: optional(&fields, schema.admin_code3),
+        geolocation: Some(GeoLocation {
+            latitude: parse_coordinate(&fields, schema.latitude, line_number)?,
+            longitude: parse_coordinate(&fields, schema.longitude, line_number)?,
+        }),
+        accuracy: match schema.accuracy {
+            Some(index) => required(&fields, index, line_number)?.parse().unwrap(),
+            None => Accuracy::NoAccuracyData,
+        },
+    })
+}
+
+/// Parse GeoNames postal data.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames postal records.
+///
+/// # Returns
+///
+/// A `Vec` of `PostalData` structs.
+///
+/// # Panics
+///
+/// Panics if a row is malformed, for example a truncated line from a partial download.
+/// Use `try_load_postal_data` to handle this as an error instead.
 pub fn load_postal_data(data: &str) -> Vec<PostalData> {
+    load_postal_data_with_schema(data, &PostalSchema::GEONAMES)
+}
+
+/// Parse GeoNames postal data, returning an error for malformed rows.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames postal records.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `PostalData` structs, or the `ParseError` for the first
+/// malformed row, such as a `ParseError::MissingField` for a row with too few fields.
+pub fn try_load_postal_data(data: &str) -> Result<Vec<PostalData>, ParseError> {
+    try_load_postal_data_with_schema(data, &PostalSchema::GEONAMES)
+}
+
+/// Parse postal data with a custom layout, such as a semicolon-delimited regional extract.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing delimited postal records.
+/// * `schema` - A `PostalSchema` describing the delimiter and columns.
+///
+/// # Returns
+///
+/// A `Vec` of `PostalData` structs.
+///
+/// # Panics
+///
+/// Panics if a row is malformed. Use `try_load_postal_data_with_schema` to handle this as
+/// an error instead.
+pub fn load_postal_data_with_schema(data: &str, schema: &PostalSchema) -> Vec<PostalData> {
+    try_load_postal_data_with_schema(data, schema).unwrap()
+}
+
+/// Parse postal data with a custom layout, returning an error for malformed rows.
+///
+/// The column count of the first row is checked against `PostalSchema::column_count`
+/// before anything is parsed, so a file with a different layout is rejected up front
+/// instead of failing part way through or reading the wrong columns.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing delimited postal records.
+/// * `schema` - A `PostalSchema` describing the delimiter and columns.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `PostalData` structs, or the `ParseError` for the first
+/// malformed row, such as a `ParseError::UnexpectedColumnCount` if the first row is too short.
+pub fn try_load_postal_data_with_schema(
+    data: &str,
+    schema: &PostalSchema,
+) -> Result<Vec<PostalData>, ParseError> {
     log::debug!("Parsing geonames data");
-    let data: Vec<PostalData> = data
-        .lines()
-        .map(|line| {
-            let fields: Vec<&str> = line.split('\t').collect();
-            PostalData {
-                country_code: fields[0].to_string(),
-                postal_code: fields[1].to_string(),
-                place_name: fields.get(2).map(|s| s.to_string()),
-                admin_name1: fields.get(3).map(|s| s.to_string()),
-                admin_code1: fields.get(4).map(|s| s.to_string()),
-                admin_name2: fields.get(5).map(|s| s.to_string()),
-                admin_code2: fields.get(6).map(|s| s.to_string()),
-                admin_name3: fields.get(7).map(|s| s.to_string()),
-                admin_code3: fields.get(8).map(|s| s.to_string()),
-                geolocation: Some(GeoLocation {
-                    latitude: fields[9].parse().unwrap(),
-                    longitude: fields[10].parse().unwrap(),
-                }),
-
-                accuracy: fields[11].parse().unwrap(),
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "parse",
+        data_type = "postal",
+        records = tracing::field::Empty
+    )
+    .entered();
+    let mut lines = data_lines(data)
+        .enumerate()
+        .filter(|(_, line)| !is_header(line, schema))
+        .peekable();
+    if let Some((index, line)) = lines.peek() {
+        check_column_count(line, index + 1, schema)?;
+    }
+    let data: Vec<PostalData> = lines
+        .map(|(index, line)| parse_postal_line_with_schema(line, index + 1, schema))
+        .collect::<Result<_, _>>()?;
+
+    log::debug!("Parsed {} geonames entries", data.len());
+    #[cfg(feature = "tracing")]
+    span.record("records", data.len());
+
+    Ok(data)
+}
+
+/// Parse GeoNames postal data, passing each record to a callback as it is parsed.
+///
+/// Unlike `load_postal_data` the records are never collected, so they can be pushed
+/// straight into another store.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames postal records.
+/// * `f` - A closure called with each parsed `PostalData` struct, in file order.
+///
+/// # Returns
+///
+/// A `Result` containing the `ParseError` for the first malformed row. Records before it
+/// have already been passed to `f`.
+pub fn for_each_postal_record<F: FnMut(PostalData)>(
+    data: &str,
+    mut f: F,
+) -> Result<(), ParseError> {
+    let mut checked = false;
+    for (index, line) in data_lines(data).enumerate() {
+        if !is_header(line, &PostalSchema::GEONAMES) {
+            if !checked {
+                check_column_count(line, index + 1, &PostalSchema::GEONAMES)?;
+                checked = true;
+            }
+            f(parse_postal_line(line, index + 1)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Read GeoNames postal data, passing each record to a callback as it is parsed.
+///
+/// Only one line is held in memory at a time, so this works for files too large to load.
+///
+/// # Arguments
+///
+/// * `reader` - A `BufRead` implementation to read tab-separated GeoNames postal records from.
+/// * `f` - A closure called with each parsed `PostalData` struct, in file order.
+///
+/// # Returns
+///
+/// A `Result` containing the `ParseError` for the first malformed or unreadable row.
+/// Records before it have already been passed to `f`.
+pub fn for_each_postal_record_from_reader<R: BufRead, F: FnMut(PostalData)>(
+    reader: R,
+    mut f: F,
+) -> Result<(), ParseError> {
+    let mut checked = false;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|error| ParseError::Io {
+            line: index + 1,
+            error,
+        })?;
+        let line = if index == 0 {
+            line.trim_start_matches(BOM)
+        } else {
+            &line
+        };
+
+        if !is_header(line, &PostalSchema::GEONAMES) {
+            if !checked {
+                check_column_count(line, index + 1, &PostalSchema::GEONAMES)?;
+                checked = true;
+            }
+            f(parse_postal_line(line, index + 1)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse GeoNames postal data directly into lightweight summaries.
+///
+/// Only the postal code and coordinates are read, which uses far less memory than
+/// `load_postal_data` for large datasets.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames postal records.
+///
+/// # Returns
+///
+/// A `Vec` of `PostalSummary` structs.
+///
+/// # Panics
+///
+/// Panics if a row is malformed, for example a truncated line from a partial download.
+/// Use `try_load_postal_summaries` to handle this as an error instead.
+pub fn load_postal_summaries(data: &str) -> Vec<PostalSummary> {
+    try_load_postal_summaries(data).unwrap()
+}
+
+/// Parse GeoNames postal data into lightweight summaries, returning an error for malformed rows.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames postal records.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `PostalSummary` structs, or the `ParseError` for the
+/// first malformed row.
+pub fn try_load_postal_summaries(data: &str) -> Result<Vec<PostalSummary>, ParseError> {
+    let schema = PostalSchema::GEONAMES;
+    data_lines(data)
+        .enumerate()
+        .filter(|(_, line)| !is_header(line, &schema))
+        .map(|(index, line)| {
+            let fields: Vec<&str> = line.split(schema.delimiter).collect();
+            let line_number = index + 1;
+
+            Ok(PostalSummary {
+                postal_code: required(&fields, schema.postal_code, line_number)?.to_string(),
+                location: GeoLocation {
+                    latitude: parse_coordinate(&fields, schema.latitude, line_number)?,
+                    longitude: parse_coordinate(&fields, schema.longitude, line_number)?,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Parse GeoNames postal data, keeping one row per postal code.
+///
+/// See `dedup_by_postal_code` for how the kept row is chosen.
+///
+/// # Arguments
+///
+/// * `data` - A `&str` containing tab-separated GeoNames postal records.
+///
+/// # Returns
+///
+/// A `Vec` of `PostalData` structs with unique postal codes per country.
+pub fn load_postal_data_dedup(data: &str) -> Vec<PostalData> {
+    dedup_by_postal_code(load_postal_data(data))
+}
+
+/// Keep only one row per postal code.
+///
+/// For each unique country and postal code, the row with the best `Accuracy` is kept.
+/// When several rows share the best accuracy, the first of them is kept with its
+/// geolocation replaced by the spherical centroid of all of them.
+/// Postal codes keep the order in which they first appear.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A `Vec` of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `PostalData` structs with unique postal codes per country.
+pub fn dedup_by_postal_code(geonames_data: Vec<PostalData>) -> Vec<PostalData> {
+    let total = geonames_data.len();
+    let mut order: Vec<(String, String)> = Vec::new();
+    let mut groups: HashMap<(String, String), Vec<PostalData>> = HashMap::new();
+
+    for geoname in geonames_data {
+        let key = (geoname.country_code.clone(), geoname.postal_code.clone());
+        groups
+            .entry(key)
+            .or_insert_with_key(|key| {
+                order.push(key.clone());
+                Vec::new()
+            })
+            .push(geoname);
+    }
+
+    let data: Vec<PostalData> = order
+        .into_iter()
+        .filter_map(|key| {
+            let rows = groups.remove(&key)?;
+            let best = rows.iter().map(|row| &row.accuracy).max()?.clone();
+            let mut tied: Vec<PostalData> = rows
+                .into_iter()
+                .filter(|row| row.accuracy == best)
+                .collect();
+
+            let centroid = if tied.len() > 1 {
+                spherical_centroid(tied.iter().filter_map(|row| row.geolocation.as_ref()))
+            } else {
+                None
+            };
+
+            let mut row = tied.swap_remove(0);
+            if centroid.is_some() {
+                row.geolocation = centroid;
             }
+            Some(row)
         })
         .collect();
 
-    log::debug!("Parsed {} geonames entries", data.len());
+    log::debug!(
+        "Deduplicated {} rows into {} postal codes",
+        total,
+        data.len()
+    );
 
     data
 }
 
+/// Stream the GeoNames postal data for all countries.
+///
+/// `get_postal_data(Country::All)` holds the whole extracted file in a `String` and every
+/// record in a `Vec`, which needs several gigabytes of RAM. This function instead extracts
+/// the file to the cache directory and parses it one line at a time, so memory use stays
+/// constant no matter how many records are read. Filter the iterator to keep only the
+/// records you need.
+///
+/// If `DISABLE_GEOCODER_CACHE` is set, the file is extracted to a temporary file instead,
+/// which is deleted when the iterator is dropped.
+/// If `GEOCODER_OFFLINE` is set, the cached file is used and nothing is downloaded.
+///
+/// # Returns
+///
+/// A `Result` containing an iterator over the parsed records.
+pub fn stream_all_countries_postal(
+) -> Result<impl Iterator<Item = Result<PostalData, ParseError>>, GeoError> {
+    stream_all_countries_postal_with_config(&DownloadConfig::default())
+}
+
+/// Stream the GeoNames postal data for all countries using a custom configuration.
+///
+/// See `stream_all_countries_postal` for details. The extracted file is cached in the
+/// directory chosen with `DownloadConfig::cache_dir`, if one is set.
+///
+/// # Arguments
+///
+/// * `config` - A `DownloadConfig` controlling how the data is fetched.
+///
+/// # Returns
+///
+/// A `Result` containing an iterator over the parsed records.
+pub fn stream_all_countries_postal_with_config(
+    config: &DownloadConfig,
+) -> Result<impl Iterator<Item = Result<PostalData, ParseError>>, GeoError> {
+    let (data_file, _) = download_file_with_status(&Country::All, Data::Postal, config)?;
+
+    log::debug!("Streaming data from {}", data_file.path().display());
+    let reader = BufReader::new(File::open(data_file.path())?);
+    let mut checked = false;
+
+    Ok(reader
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match line {
+            Ok(line) if index == 0 => Ok((index, line.trim_start_matches(BOM).to_string())),
+            Ok(line) => Ok((index, line)),
+            Err(error) => Err(ParseError::Io {
+                line: index + 1,
+                error,
+            }),
+        })
+        .filter(|line| !matches!(line, Ok((_, line)) if is_header(line, &PostalSchema::GEONAMES)))
+        .map(move |line| {
+            // keep a temporary file until the iterator is dropped
+            let _ = &data_file;
+            let (index, line) = line?;
+            if !checked {
+                check_column_count(&line, index + 1, &PostalSchema::GEONAMES)?;
+                checked = true;
+            }
+            parse_postal_line(&line, index + 1)
+        }))
+}
+
 /// Get Postal data for a specific country.
 ///
 /// # Arguments
@@ -59,17 +510,360 @@ pub fn load_postal_data(data: &str) -> Vec<PostalData> {
 ///
 /// A `Vec` of `PostalData` structs.
 pub fn get_postal_data(country: Country) -> Vec<PostalData> {
+    warn_if_all_countries(&country);
     let data = download(&country, Data::Postal).unwrap();
     load_postal_data(&data)
 }
 
+/// Get lightweight postal summaries for a specific country.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+///
+/// # Returns
+///
+/// A `Vec` of `PostalSummary` structs.
+///
+/// # Panics
+///
+/// Panics if the data cannot be downloaded or a row is malformed.
+pub fn get_postal_summaries(country: Country) -> Vec<PostalSummary> {
+    let data = download(&country, Data::Postal).unwrap();
+    load_postal_summaries(&data)
+}
+
+/// Get Postal data for a specific country using a custom configuration.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `config` - A `DownloadConfig` controlling how the data is fetched.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `PostalData` structs.
+pub fn get_postal_data_with_config(
+    country: Country,
+    config: &DownloadConfig,
+) -> Result<Vec<PostalData>, GeoError> {
+    warn_if_all_countries(&country);
+    let data = download_with_config(&country, Data::Postal, config)?;
+    Ok(try_load_postal_data(&data)?)
+}
+
+/// Get Postal data for a specific country, refusing to buffer the whole world by accident.
+///
+/// `Country::All` holds every postal code in memory, which needs several gigabytes of RAM.
+/// Unless `allow_all_countries` is set, it returns an error instead; use
+/// `stream_all_countries_postal` to read it with constant memory.
+///
+/// # Arguments
+///
+/// * `country` - A `Country` enum representing the country.
+/// * `allow_all_countries` - A `bool` opting in to loading `Country::All` into memory.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `PostalData` structs, or
+/// `GeoError::DatasetTooLargeWithoutStreaming` if `Country::All` was not allowed.
+pub fn get_postal_data_checked(
+    country: Country,
+    allow_all_countries: bool,
+) -> Result<Vec<PostalData>, GeoError> {
+    if country == Country::All && !allow_all_countries {
+        return Err(GeoError::DatasetTooLargeWithoutStreaming);
+    }
+
+    get_postal_data_with_config(country, &DownloadConfig::default())
+}
+
+fn warn_if_all_countries(country: &Country) {
+    if *country == Country::All {
+        log::warn!(
+            "Loading postal data for all countries into memory needs several gigabytes of RAM; \
+             use stream_all_countries_postal to read it with constant memory"
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geonames::cache::cache_path_in;
+
+    #[test_log::test]
+    fn test_get_postal_data_checked_all_countries() {
+        assert!(matches!(
+            get_postal_data_checked(Country::All, false),
+            Err(GeoError::DatasetTooLargeWithoutStreaming)
+        ));
+    }
 
     #[test_log::test]
     fn test_load_postal() {
         let data = get_postal_data(Country::UnitedKingdom);
         assert!(!data.is_empty());
     }
+
+    #[test_log::test]
+    fn test_load_postal_data_with_schema() {
+        let schema = PostalSchema {
+            delimiter: ';',
+            country_code: 0,
+            postal_code: 1,
+            place_name: Some(4),
+            admin_name1: None,
+            admin_code1: None,
+            admin_name2: None,
+            admin_code2: None,
+            admin_name3: None,
+            admin_code3: None,
+            latitude: 2,
+            longitude: 3,
+            accuracy: None,
+        };
+        let data = load_postal_data_with_schema("GB;CM8;51.8;0.63;Witham\n", &schema);
+
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].postal_code, "CM8");
+        assert_eq!(data[0].place_name.as_deref(), Some("Witham"));
+        assert_eq!(data[0].admin_name1, None);
+        assert_eq!(data[0].geolocation, Some(GeoLocation::from((51.8, 0.63))));
+        assert_eq!(data[0].accuracy, Accuracy::NoAccuracyData);
+
+        let line = "GB\tCM8\tWitham\tEngland\tENG\tEssex\t11E\tBraintree\t22UC\t51.8\t0.63\t6";
+        assert_eq!(
+            load_postal_data_with_schema(line, &PostalSchema::default()),
+            load_postal_data(line)
+        );
+    }
+
+    #[test_log::test]
+    fn test_try_load_postal_truncated_row() {
+        let data = "GB\tCM8\tWitham\t\t\t\t\t\t\t51.8\t0.63\t6\n\
+                    GB\tCM9\tMaldon\t\t\t\t\t\t\t51.7";
+        let first_row = &data[..data.find('\n').unwrap()];
+
+        assert_eq!(try_load_postal_data(first_row).unwrap().len(), 1);
+        assert!(matches!(
+            try_load_postal_data(data),
+            Err(ParseError::MissingField { line: 2, index: 10 })
+        ));
+
+        let summaries = try_load_postal_summaries(first_row).unwrap();
+        assert_eq!(summaries[0].postal_code, "CM8");
+        assert_eq!(summaries[0].location, GeoLocation::from((51.8, 0.63)));
+        assert!(matches!(
+            try_load_postal_summaries(data),
+            Err(ParseError::MissingField { line: 2, index: 10 })
+        ));
+    }
+
+    #[test_log::test]
+    fn test_try_load_postal_data_column_count() {
+        assert_eq!(PostalSchema::GEONAMES.column_count(), 12);
+
+        let data = "GB,CM8,Witham,51.8,0.63\n";
+        assert!(matches!(
+            try_load_postal_data(data),
+            Err(ParseError::UnexpectedColumnCount {
+                line: 1,
+                found: 1,
+                expected: 12,
+            })
+        ));
+
+        let schema = PostalSchema {
+            delimiter: ',',
+            place_name: Some(2),
+            admin_name1: None,
+            admin_code1: None,
+            admin_name2: None,
+            admin_code2: None,
+            admin_name3: None,
+            admin_code3: None,
+            latitude: 3,
+            longitude: 4,
+            accuracy: None,
+            ..PostalSchema::GEONAMES
+        };
+        assert_eq!(schema.column_count(), 5);
+        assert_eq!(
+            try_load_postal_data_with_schema(data, &schema)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test_log::test]
+    fn test_load_postal_data_bom_and_crlf() {
+        let data = "\u{feff}GB\tCM8\tWitham\t\t\t\t\t\t\t51.8\t0.63\t6\r\n\
+                    GB\tCM9\tMaldon\t\t\t\t\t\t\t51.7\t0.68\t\r\n";
+
+        let records = load_postal_data(data);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].country_code, "GB");
+        assert_eq!(records[0].accuracy, Accuracy::Centroid);
+        assert_eq!(records[1].accuracy, Accuracy::NoAccuracyData);
+        assert_eq!(load_postal_summaries(data)[0].postal_code, "CM8");
+    }
+
+    #[test_log::test]
+    fn test_for_each_postal_record() {
+        let data = "country_code\tpostal_code\n\
+                    GB\tCM8\tWitham\t\t\t\t\t\t\t51.8\t0.63\t6\n\
+                    GB\tCM9\tMaldon\t\t\t\t\t\t\t51.7\t0.68\t\n";
+
+        let mut postcodes = Vec::new();
+        for_each_postal_record(data, |record| postcodes.push(record.postal_code)).unwrap();
+        assert_eq!(postcodes, ["CM8", "CM9"]);
+
+        let mut records = Vec::new();
+        for_each_postal_record_from_reader(data.as_bytes(), |record| records.push(record)).unwrap();
+        assert_eq!(records, load_postal_data(data));
+
+        let mut count = 0;
+        let error =
+            for_each_postal_record("GB\tCM8\tWitham\t\t\t\t\t\t\t51.8\t0.63\t6\nGB", |_| {
+                count += 1
+            });
+        assert!(matches!(
+            error,
+            Err(ParseError::MissingField { line: 2, .. })
+        ));
+        assert_eq!(count, 1);
+    }
+
+    #[test_log::test]
+    fn test_stream_all_countries_postal_custom_cache_dir() {
+        let root = std::env::temp_dir().join("geo_rust-test-stream-cache-dir");
+        let cache_path = cache_path_in(&root, &Country::All, Data::Postal);
+        std::fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &cache_path,
+            "GB\tCM8\tWitham\t\t\t\t\t\t\t51.8\t0.63\t6\n\
+             AD\tAD100\tCanillo\t\t\t\t\t\t\t42.58\t1.67\t6\n",
+        )
+        .unwrap();
+
+        let config = DownloadConfig::new().offline(true).cache_dir(&root);
+        let postcodes: Vec<String> = stream_all_countries_postal_with_config(&config)
+            .unwrap()
+            .map(|record| record.unwrap().postal_code)
+            .collect();
+        assert_eq!(postcodes, ["CM8", "AD100"]);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_stream_all_countries_postal_column_count() {
+        let root = std::env::temp_dir().join("geo_rust-test-stream-column-count");
+        let cache_path = cache_path_in(&root, &Country::All, Data::Postal);
+        std::fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+        std::fs::write(&cache_path, "GB,CM8,Witham,51.8,0.63\n").unwrap();
+
+        let config = DownloadConfig::new().offline(true).cache_dir(&root);
+        let mut records = stream_all_countries_postal_with_config(&config).unwrap();
+        assert!(matches!(
+            records.next(),
+            Some(Err(ParseError::UnexpectedColumnCount {
+                line: 1,
+                found: 1,
+                expected: 12,
+            }))
+        ));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test_log::test]
+    fn test_for_each_postal_record_column_count() {
+        let mut count = 0;
+        let error = for_each_postal_record("GB,CM8,Witham,51.8,0.63\n", |_| count += 1);
+        assert!(matches!(
+            error,
+            Err(ParseError::UnexpectedColumnCount {
+                line: 1,
+                found: 1,
+                expected: 12,
+            })
+        ));
+        assert_eq!(count, 0);
+    }
+
+    #[test_log::test]
+    fn test_for_each_postal_record_from_reader_column_count() {
+        let data = "country_code\tpostal_code\nGB\tCM8\tWitham\t51.8\t0.63\n";
+        let mut count = 0;
+        let error = for_each_postal_record_from_reader(data.as_bytes(), |_| count += 1);
+        assert!(matches!(
+            error,
+            Err(ParseError::UnexpectedColumnCount {
+                line: 2,
+                found: 5,
+                expected: 12,
+            })
+        ));
+        assert_eq!(count, 0);
+    }
+
+    #[test_log::test]
+    fn test_load_postal_summaries() {
+        let data = "country_code\tpostal_code\n\
+                    GB\tCM8\tWitham\tEngland\tENG\tEssex\t11E\tBraintree\t22UC\t51.8\t0.63\t6\n";
+
+        let summaries = load_postal_summaries(data);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(
+            Some(summaries[0].clone()),
+            load_postal_data(data)[0].to_summary()
+        );
+    }
+
+    #[test_log::test]
+    fn test_load_postal_data_dedup() {
+        let data = load_postal_data_dedup(
+            "GB\tCM8\tWitham\t\t\t\t\t\t\t51.8\t0.6\t4\n\
+             GB\tCM8\tRivenhall\t\t\t\t\t\t\t51.9\t0.7\t6\n\
+             GB\tCM9\tMaldon\t\t\t\t\t\t\t51.7\t0.6\t1\n\
+             GB\tCM9\tHeybridge\t\t\t\t\t\t\t51.7\t0.8\t1\n\
+             US\tCM8\tElsewhere\t\t\t\t\t\t\t40.0\t-70.0\t1\n",
+        );
+
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[0].place_name.as_deref(), Some("Rivenhall"));
+
+        assert_eq!(data[1].place_name.as_deref(), Some("Maldon"));
+        let centroid = data[1].geolocation.as_ref().unwrap();
+        assert!((centroid.latitude - 51.7).abs() < 0.001);
+        assert!((centroid.longitude - 0.7).abs() < 0.001);
+
+        assert_eq!(data[2].country_code, "US");
+    }
+
+    #[test_log::test]
+    fn test_parse_postal_line() {
+        let data = parse_postal_line("GB\tCM8\tWitham\t\t\t\t\t\t\t51.8\t0.63\t6", 1).unwrap();
+        assert_eq!(data.postal_code, "CM8");
+        assert_eq!(data.accuracy, crate::Accuracy::Centroid);
+
+        let error = parse_postal_line("GB\tCM8\tWitham", 3).unwrap_err();
+        assert!(matches!(
+            error,
+            ParseError::MissingField { line: 3, index: 9 }
+        ));
+
+        let error = parse_postal_line("GB\tCM8\t\t\t\t\t\t\t\tnorth\t0.63\t6", 4).unwrap_err();
+        assert!(matches!(
+            error,
+            ParseError::InvalidField {
+                line: 4,
+                index: 9,
+                ..
+            }
+        ));
+    }
 }
@@ -0,0 +1,73 @@
+/// The layout of a delimited postal data file.
+///
+/// Each field holds the 0-based column index it is read from. Optional fields set to `None`
+/// are left empty, and a `None` accuracy is read as `Accuracy::NoAccuracyData`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostalSchema {
+    pub delimiter: char,
+    pub country_code: usize,
+    pub postal_code: usize,
+    pub place_name: Option<usize>,
+    pub admin_name1: Option<usize>,
+    pub admin_code1: Option<usize>,
+    pub admin_name2: Option<usize>,
+    pub admin_code2: Option<usize>,
+    pub admin_name3: Option<usize>,
+    pub admin_code3: Option<usize>,
+    pub latitude: usize,
+    pub longitude: usize,
+    pub accuracy: Option<usize>,
+}
+
+impl PostalSchema {
+    /// The tab-separated layout of the GeoNames postal files.
+    pub const GEONAMES: PostalSchema = PostalSchema {
+        delimiter: '\t',
+        country_code: 0,
+        postal_code: 1,
+        place_name: Some(2),
+        admin_name1: Some(3),
+        admin_code1: Some(4),
+        admin_name2: Some(5),
+        admin_code2: Some(6),
+        admin_name3: Some(7),
+        admin_code3: Some(8),
+        latitude: 9,
+        longitude: 10,
+        accuracy: Some(11),
+    };
+}
+
+impl PostalSchema {
+    /// Get the number of columns a row needs for every field in the layout to be read.
+    ///
+    /// # Returns
+    ///
+    /// A `usize` representing one more than the highest column index.
+    pub fn column_count(&self) -> usize {
+        [
+            Some(self.country_code),
+            Some(self.postal_code),
+            self.place_name,
+            self.admin_name1,
+            self.admin_code1,
+            self.admin_name2,
+            self.admin_code2,
+            self.admin_name3,
+            self.admin_code3,
+            Some(self.latitude),
+            Some(self.longitude),
+            self.accuracy,
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .map_or(0, |index| index + 1)
+    }
+}
+
+impl Default for PostalSchema {
+    fn default() -> Self {
+        PostalSchema::GEONAMES
+    }
+}
@@ -1,6 +1,15 @@
 use crate::GeoLocation;
 
+/// The Earth radius in kilometers used by `calculate_distance`.
 pub const EARTH_RADIUS: f64 = 6371.0;
+/// The IUGG mean Earth radius in kilometers.
+pub const EARTH_RADIUS_MEAN: f64 = 6371.0088;
+/// The authalic (equal-area) Earth radius in kilometers.
+pub const EARTH_RADIUS_AUTHALIC: f64 = 6371.0072;
+/// The WGS 84 equatorial Earth radius in kilometers.
+pub const EARTH_RADIUS_EQUATORIAL: f64 = 6378.137;
+/// The WGS 84 polar Earth radius in kilometers.
+pub const EARTH_RADIUS_POLAR: f64 = 6356.7523;
 
 #[derive(Debug)]
 pub struct BoundingBox {
@@ -32,6 +41,75 @@ impl BoundingBox {
             max_lon: centre.longitude + lon_diff,
         }
     }
+
+    /// Create a new rectangular `BoundingBox` struct with independent dimensions.
+    ///
+    /// Unlike `new`, which extends the same distance in every direction, each axis is sized
+    /// separately. This is useful for corridor searches, for example a box 20 km north-south
+    /// by 5 km east-west along a road.
+    ///
+    /// # Arguments
+    ///
+    /// * `centre` - A `GeoLocation` struct representing the center of the bounding box.
+    /// * `lat_km` - A `f64` representing the total north-south height in kilometers.
+    /// * `lon_km` - A `f64` representing the total east-west width in kilometers, measured
+    ///   at the centre's latitude.
+    ///
+    /// # Returns
+    ///
+    /// A `BoundingBox` struct representing the bounding box.
+    pub fn with_dimensions(centre: &GeoLocation, lat_km: f64, lon_km: f64) -> Self {
+        let km_per_degree = EARTH_RADIUS.to_radians();
+        let lat_diff = lat_km / 2.0 / km_per_degree;
+        let lon_diff = lon_km / 2.0 / (km_per_degree * centre.latitude.to_radians().cos());
+
+        BoundingBox {
+            min_lat: centre.latitude - lat_diff,
+            max_lat: centre.latitude + lat_diff,
+            min_lon: centre.longitude - lon_diff,
+            max_lon: centre.longitude + lon_diff,
+        }
+    }
+
+    /// Calculate the distance from a location to the nearest edge or corner of the bounding box.
+    ///
+    /// The location is clamped onto the box and the haversine distance to that point is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `GeoLocation` struct representing the location.
+    ///
+    /// # Returns
+    ///
+    /// A `f64` representing the distance in kilometers, or `0.0` if the location is inside the box.
+    pub fn distance_to(&self, location: &GeoLocation) -> f64 {
+        if is_within_bounding_box(location, self) {
+            return 0.0;
+        }
+
+        let nearest = GeoLocation {
+            latitude: location.latitude.clamp(self.min_lat, self.max_lat),
+            longitude: location.longitude.clamp(self.min_lon, self.max_lon),
+        };
+
+        calculate_distance(location, &nearest)
+    }
+
+    /// Calculate the area of the bounding box on the sphere.
+    ///
+    /// The area between two lines of latitude shrinks towards the poles, so a 1° box at the
+    /// equator is about 12,364 km² but much smaller near a pole.
+    ///
+    /// # Returns
+    ///
+    /// A `f64` representing the area in square kilometers.
+    pub fn area_km2(&self) -> f64 {
+        let min_lat = self.min_lat.clamp(-90.0, 90.0).to_radians();
+        let max_lat = self.max_lat.clamp(-90.0, 90.0).to_radians();
+        let width = (self.max_lon - self.min_lon).abs().min(360.0).to_radians();
+
+        EARTH_RADIUS * EARTH_RADIUS * width * (max_lat.sin() - min_lat.sin()).abs()
+    }
 }
 
 /// Calculate the haversine distance between two locations.
@@ -45,6 +123,25 @@ impl BoundingBox {
 ///
 /// A `f64` representing the distance between the two locations in kilometers.
 pub fn calculate_distance(location_1: &GeoLocation, location_2: &GeoLocation) -> f64 {
+    calculate_distance_with_radius(location_1, location_2, EARTH_RADIUS)
+}
+
+/// Calculate the haversine distance between two locations on a sphere of a given radius.
+///
+/// # Arguments
+///
+/// * `location_1` - A `Location` struct representing the first location.
+/// * `location_2` - A `Location` struct representing the second location.
+/// * `radius_km` - A `f64` representing the radius of the sphere in kilometers, e.g. `EARTH_RADIUS_AUTHALIC`.
+///
+/// # Returns
+///
+/// A `f64` representing the distance between the two locations in kilometers.
+pub fn calculate_distance_with_radius(
+    location_1: &GeoLocation,
+    location_2: &GeoLocation,
+    radius_km: f64,
+) -> f64 {
     let d_lat = (location_2.latitude - location_1.latitude).to_radians();
     let d_lon = (location_2.longitude - location_1.longitude).to_radians();
 
@@ -55,11 +152,543 @@ pub fn calculate_distance(location_1: &GeoLocation, location_2: &GeoLocation) ->
             * (d_lon / 2.0).sin();
     let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
 
-    EARTH_RADIUS * c
+    radius_km * c
+}
+
+/// Approximate the distance between two locations using an equirectangular projection.
+///
+/// This needs a single `cos` and `sqrt` rather than the trigonometry of the haversine formula,
+/// so it is much cheaper over millions of rows. It is within a fraction of a percent for
+/// distances of a few hundred kilometers away from the poles, but the error grows with distance
+/// and latitude, so use it for coarse prefiltering and `calculate_distance` for final ranking.
+///
+/// # Arguments
+///
+/// * `location_1` - A `Location` struct representing the first location.
+/// * `location_2` - A `Location` struct representing the second location.
+///
+/// # Returns
+///
+/// A `f64` representing the approximate distance between the two locations in kilometers.
+pub fn calculate_distance_fast(location_1: &GeoLocation, location_2: &GeoLocation) -> f64 {
+    let d_lat = (location_2.latitude - location_1.latitude).to_radians();
+    // take the short way round across the antimeridian
+    let d_lon = ((location_2.longitude - location_1.longitude + 180.0).rem_euclid(360.0) - 180.0)
+        .to_radians();
+    let mean_lat = ((location_1.latitude + location_2.latitude) / 2.0).to_radians();
+
+    let x = d_lon * mean_lat.cos();
+    EARTH_RADIUS * (x * x + d_lat * d_lat).sqrt()
+}
+
+// the initial bearing from one location to another, in radians clockwise from north
+fn bearing_radians(from: &GeoLocation, to: &GeoLocation) -> f64 {
+    let from_lat = from.latitude.to_radians();
+    let to_lat = to.latitude.to_radians();
+    let d_lon = (to.longitude - from.longitude).to_radians();
+
+    let y = d_lon.sin() * to_lat.cos();
+    let x = from_lat.cos() * to_lat.sin() - from_lat.sin() * to_lat.cos() * d_lon.cos();
+    y.atan2(x)
+}
+
+/// Calculate the initial bearing from one location to another along a great circle.
+///
+/// # Arguments
+///
+/// * `from` - A `GeoLocation` struct representing the starting location.
+/// * `to` - A `GeoLocation` struct representing the destination.
+///
+/// # Returns
+///
+/// A `f64` representing the bearing in degrees clockwise from north, in the range `0.0..360.0`.
+pub fn initial_bearing(from: &GeoLocation, to: &GeoLocation) -> f64 {
+    bearing_radians(from, to).to_degrees().rem_euclid(360.0)
+}
+
+// the location reached by travelling a distance in kilometers along a great circle
+fn destination(from: &GeoLocation, bearing: f64, distance: f64) -> GeoLocation {
+    let angular_distance = distance / EARTH_RADIUS;
+    let lat = from.latitude.to_radians();
+    let to_lat = (lat.sin() * angular_distance.cos()
+        + lat.cos() * angular_distance.sin() * bearing.cos())
+    .asin();
+    let d_lon = (bearing.sin() * angular_distance.sin() * lat.cos())
+        .atan2(angular_distance.cos() - lat.sin() * to_lat.sin());
+
+    GeoLocation::new_normalized(to_lat.to_degrees(), from.longitude + d_lon.to_degrees())
+}
+
+/// Calculate the cross-track distance from a point to a great-circle path.
+///
+/// This is the shortest distance from the point to the great circle through `path_start`
+/// and `path_end`, for example how far a stop is off a route.
+///
+/// # Arguments
+///
+/// * `point` - A `GeoLocation` struct representing the point.
+/// * `path_start` - A `GeoLocation` struct representing the start of the path.
+/// * `path_end` - A `GeoLocation` struct representing the end of the path.
+///
+/// # Returns
+///
+/// A `f64` representing the distance in kilometers, negative if the point is to the left
+/// of the path when travelling from `path_start` to `path_end`, and positive if it is to the right.
+pub fn cross_track_distance(
+    point: &GeoLocation,
+    path_start: &GeoLocation,
+    path_end: &GeoLocation,
+) -> f64 {
+    let angular_distance = calculate_distance(path_start, point) / EARTH_RADIUS;
+    let bearing_to_point = bearing_radians(path_start, point);
+    let bearing_to_end = bearing_radians(path_start, path_end);
+
+    (angular_distance.sin() * (bearing_to_point - bearing_to_end).sin()).asin() * EARTH_RADIUS
+}
+
+/// Calculate the along-track distance of a point on a great-circle path.
+///
+/// This is the distance from `path_start` to the closest point on the path to `point`,
+/// the companion of `cross_track_distance`.
+///
+/// # Arguments
+///
+/// * `point` - A `GeoLocation` struct representing the point.
+/// * `path_start` - A `GeoLocation` struct representing the start of the path.
+/// * `path_end` - A `GeoLocation` struct representing the end of the path.
+///
+/// # Returns
+///
+/// A `f64` representing the distance in kilometers, negative if the closest point is
+/// behind `path_start`.
+pub fn along_track_distance(
+    point: &GeoLocation,
+    path_start: &GeoLocation,
+    path_end: &GeoLocation,
+) -> f64 {
+    let angular_distance = calculate_distance(path_start, point) / EARTH_RADIUS;
+    let cross_track = cross_track_distance(point, path_start, path_end) / EARTH_RADIUS;
+    let bearing_to_point = bearing_radians(path_start, point);
+    let bearing_to_end = bearing_radians(path_start, path_end);
+
+    let along_track = (angular_distance.cos() / cross_track.cos())
+        .clamp(-1.0, 1.0)
+        .acos();
+    along_track * (bearing_to_point - bearing_to_end).cos().signum() * EARTH_RADIUS
+}
+
+/// Calculate the midpoint of the great-circle path between two locations.
+///
+/// # Arguments
+///
+/// * `location_1` - A `GeoLocation` struct representing the first location.
+/// * `location_2` - A `GeoLocation` struct representing the second location.
+///
+/// # Returns
+///
+/// A `GeoLocation` halfway between the locations, with its longitude in `[-180, 180]`.
+pub fn midpoint(location_1: &GeoLocation, location_2: &GeoLocation) -> GeoLocation {
+    let lat_1 = location_1.latitude.to_radians();
+    let lat_2 = location_2.latitude.to_radians();
+    let d_lon = (location_2.longitude - location_1.longitude).to_radians();
+
+    let bx = lat_2.cos() * d_lon.cos();
+    let by = lat_2.cos() * d_lon.sin();
+    let latitude = (lat_1.sin() + lat_2.sin()).atan2(((lat_1.cos() + bx).powi(2) + by * by).sqrt());
+    let longitude = location_1.longitude.to_radians() + by.atan2(lat_1.cos() + bx);
+
+    GeoLocation {
+        latitude: latitude.to_degrees(),
+        longitude: (longitude.to_degrees() + 180.0).rem_euclid(360.0) - 180.0,
+    }
+}
+
+/// Calculate a location part of the way along the great-circle path between two locations.
+///
+/// This uses spherical linear interpolation, so evenly spaced fractions give evenly spaced
+/// locations, which is useful for animating movement between two points.
+///
+/// # Arguments
+///
+/// * `location_1` - A `GeoLocation` struct representing the start of the path.
+/// * `location_2` - A `GeoLocation` struct representing the end of the path.
+/// * `fraction` - A `f64` representing how far along the path to go, from `0.0` at
+///   `location_1` to `1.0` at `location_2`.
+///
+/// # Returns
+///
+/// A `GeoLocation` along the path. Identical locations have no path between them, so the
+/// location itself is returned; antipodal locations have no unique path, so the nearer
+/// endpoint is returned.
+pub fn interpolate(
+    location_1: &GeoLocation,
+    location_2: &GeoLocation,
+    fraction: f64,
+) -> GeoLocation {
+    let angular_distance = calculate_distance(location_1, location_2) / EARTH_RADIUS;
+    if angular_distance.sin().abs() < 1e-12 {
+        return if fraction < 0.5 {
+            location_1.clone()
+        } else {
+            location_2.clone()
+        };
+    }
+
+    let weight_1 = ((1.0 - fraction) * angular_distance).sin() / angular_distance.sin();
+    let weight_2 = (fraction * angular_distance).sin() / angular_distance.sin();
+
+    let (lat_1, lon_1) = (
+        location_1.latitude.to_radians(),
+        location_1.longitude.to_radians(),
+    );
+    let (lat_2, lon_2) = (
+        location_2.latitude.to_radians(),
+        location_2.longitude.to_radians(),
+    );
+    let x = weight_1 * lat_1.cos() * lon_1.cos() + weight_2 * lat_2.cos() * lon_2.cos();
+    let y = weight_1 * lat_1.cos() * lon_1.sin() + weight_2 * lat_2.cos() * lon_2.sin();
+    let z = weight_1 * lat_1.sin() + weight_2 * lat_2.sin();
+
+    GeoLocation {
+        latitude: z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        longitude: y.atan2(x).to_degrees(),
+    }
+}
+
+/// Sample the great-circle path between two locations as a polyline.
+///
+/// The points are evenly spaced along the path using `interpolate`. Paths crossing the
+/// antimeridian are not split, so consecutive points can jump from a longitude near 180 to
+/// one near -180; callers drawing the line on a flat map may need to split it there.
+///
+/// # Arguments
+///
+/// * `location_1` - A `GeoLocation` struct representing the start of the path.
+/// * `location_2` - A `GeoLocation` struct representing the end of the path.
+/// * `segments` - A `usize` representing the number of segments; values below 1 are treated as 1.
+///
+/// # Returns
+///
+/// A `Vec` of `segments + 1` `GeoLocation` structs, starting at `location_1` and ending at
+/// `location_2`.
+pub fn great_circle_path(
+    location_1: &GeoLocation,
+    location_2: &GeoLocation,
+    segments: usize,
+) -> Vec<GeoLocation> {
+    let segments = segments.max(1);
+
+    let mut path = Vec::with_capacity(segments + 1);
+    path.push(location_1.clone());
+    for segment in 1..segments {
+        path.push(interpolate(
+            location_1,
+            location_2,
+            segment as f64 / segments as f64,
+        ));
+    }
+    path.push(location_2.clone());
+
+    path
+}
+
+/// Check if two locations are within a certain distance of each other.
+///
+/// The boundary is inclusive, so locations exactly `radius` kilometers apart are within the radius.
+///
+/// # Arguments
+///
+/// * `location_1` - A `Location` struct representing the first location.
+/// * `location_2` - A `Location` struct representing the second location.
+/// * `radius` - A `f64` representing the radius in kilometers.
+///
+/// # Returns
+///
+/// A `bool` indicating whether the haversine distance between the locations is at most `radius`.
+pub fn within_radius(location_1: &GeoLocation, location_2: &GeoLocation, radius: f64) -> bool {
+    calculate_distance(location_1, location_2) <= radius
+}
+
+/// Calculate the spherical centroid of a set of locations.
+///
+/// Each location is converted to a point on the unit sphere and the points are averaged,
+/// which, unlike averaging latitudes and longitudes, is correct across the antimeridian.
+///
+/// # Arguments
+///
+/// * `locations` - An iterator of `GeoLocation` references.
+///
+/// # Returns
+///
+/// An `Option` containing the centroid, or `None` if there are no locations or they cancel out
+/// (for example two antipodal points).
+pub fn spherical_centroid<'a>(
+    locations: impl IntoIterator<Item = &'a GeoLocation>,
+) -> Option<GeoLocation> {
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    let mut count = 0;
+
+    for location in locations {
+        let latitude = location.latitude.to_radians();
+        let longitude = location.longitude.to_radians();
+        x += latitude.cos() * longitude.cos();
+        y += latitude.cos() * longitude.sin();
+        z += latitude.sin();
+        count += 1;
+    }
+
+    if count == 0 || (x * x + y * y + z * z).sqrt() < 1e-12 {
+        return None;
+    }
+
+    Some(GeoLocation {
+        latitude: z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        longitude: y.atan2(x).to_degrees(),
+    })
+}
+
+/// Calculate the convex hull of a set of locations.
+///
+/// The locations are projected onto a plane tangent to their centroid, so this is accurate
+/// for regional extents but not for sets spanning a large part of the globe. The hull is
+/// tighter than the box returned by `bounding_box_of`.
+///
+/// # Arguments
+///
+/// * `locations` - A slice of `GeoLocation` structs.
+///
+/// # Returns
+///
+/// A `Vec` of the hull's vertices in counter-clockwise order, without repeating the first
+/// vertex. Duplicate and collinear points are dropped, so fewer than three locations are
+/// returned if the input has no area.
+pub fn convex_hull(locations: &[GeoLocation]) -> Vec<GeoLocation> {
+    let Some(centre) = spherical_centroid(locations) else {
+        return locations.first().cloned().into_iter().collect();
+    };
+
+    // project onto a local plane, taking the short way round across the antimeridian
+    let scale = centre.latitude.to_radians().cos();
+    let mut points: Vec<(f64, f64, &GeoLocation)> = locations
+        .iter()
+        .map(|location| {
+            let d_lon = (location.longitude - centre.longitude + 180.0).rem_euclid(360.0) - 180.0;
+            (d_lon * scale, location.latitude - centre.latitude, location)
+        })
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+
+    if points.len() < 3 {
+        return points
+            .into_iter()
+            .map(|(_, _, location)| location.clone())
+            .collect();
+    }
+
+    let cross = |o: &(f64, f64, &GeoLocation),
+                 a: &(f64, f64, &GeoLocation),
+                 b: &(f64, f64, &GeoLocation)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+
+    // Andrew's monotone chain, building the lower hull then the upper hull
+    let mut hull: Vec<(f64, f64, &GeoLocation)> = Vec::with_capacity(points.len() * 2);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2
+                && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], &point) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // the last point of each half is the first point of the other
+        hull.pop();
+    }
+
+    hull.into_iter()
+        .map(|(_, _, location)| location.clone())
+        .collect()
+}
+
+/// A circle on a local plane, as its centre in kilometers and its radius.
+type PlaneCircle = ((f64, f64), f64);
+
+fn plane_circle_from_pair(a: (f64, f64), b: (f64, f64)) -> PlaneCircle {
+    let centre = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    (centre, (a.0 - b.0).hypot(a.1 - b.1) / 2.0)
+}
+
+fn plane_circle_from_triple(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> PlaneCircle {
+    let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+    if d.abs() < 1e-12 {
+        // collinear points are enclosed by the circle through the two furthest apart
+        return [
+            plane_circle_from_pair(a, b),
+            plane_circle_from_pair(a, c),
+            plane_circle_from_pair(b, c),
+        ]
+        .into_iter()
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+        .unwrap();
+    }
+
+    let (a2, b2, c2) = (
+        a.0 * a.0 + a.1 * a.1,
+        b.0 * b.0 + b.1 * b.1,
+        c.0 * c.0 + c.1 * c.1,
+    );
+    let centre = (
+        (a2 * (b.1 - c.1) + b2 * (c.1 - a.1) + c2 * (a.1 - b.1)) / d,
+        (a2 * (c.0 - b.0) + b2 * (a.0 - c.0) + c2 * (b.0 - a.0)) / d,
+    );
+    (centre, (a.0 - centre.0).hypot(a.1 - centre.1))
+}
+
+fn plane_circle_contains(circle: &PlaneCircle, point: (f64, f64)) -> bool {
+    let ((x, y), radius) = *circle;
+    (point.0 - x).hypot(point.1 - y) <= radius * (1.0 + 1e-9) + 1e-9
+}
+
+// Fisher-Yates shuffle driven by a fixed-seed linear congruential generator, as Welzl's
+// algorithm is only expected linear time on points in random order
+fn shuffle<T>(items: &mut [T]) {
+    let mut seed: u64 = 0x2545f4914f6cdd1d;
+    for i in (1..items.len()).rev() {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let j = ((seed >> 33) % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Calculate the minimum enclosing circle of a set of locations.
+///
+/// Welzl's algorithm is run on an azimuthal equidistant projection centred on the
+/// locations' centroid, so like `convex_hull` this is accurate for regional extents but not
+/// for sets spanning a large part of the globe. The circle is usually a tighter fit than `bounding_box_of`.
+/// The points are shuffled with a fixed seed first, so the expected running time is linear
+/// even for sorted input, such as the output of `sort_by_distance`, and the result is
+/// the same on every call.
+///
+/// # Arguments
+///
+/// * `locations` - A slice of `GeoLocation` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the centre of the circle and its radius in kilometers, or `None`
+/// if there are no locations. The radius is the great-circle distance from the centre to
+/// the furthest location, so every location is within it.
+pub fn minimum_enclosing_circle(locations: &[GeoLocation]) -> Option<(GeoLocation, f64)> {
+    let first = locations.first()?;
+    let origin = spherical_centroid(locations).unwrap_or_else(|| first.clone());
+
+    // azimuthal equidistant projection, which preserves distances from the origin
+    let mut points: Vec<(f64, f64)> = locations
+        .iter()
+        .map(|location| {
+            let distance = calculate_distance(&origin, location);
+            let bearing = bearing_radians(&origin, location);
+            (distance * bearing.sin(), distance * bearing.cos())
+        })
+        .collect();
+    shuffle(&mut points);
+
+    // iterative Welzl: each point outside the circle must lie on the boundary of the next one
+    let mut circle: PlaneCircle = (points[0], 0.0);
+    for i in 1..points.len() {
+        if plane_circle_contains(&circle, points[i]) {
+            continue;
+        }
+        circle = (points[i], 0.0);
+        for j in 0..i {
+            if plane_circle_contains(&circle, points[j]) {
+                continue;
+            }
+            circle = plane_circle_from_pair(points[i], points[j]);
+            for k in 0..j {
+                if !plane_circle_contains(&circle, points[k]) {
+                    circle = plane_circle_from_triple(points[i], points[j], points[k]);
+                }
+            }
+        }
+    }
+
+    let ((x, y), _) = circle;
+    let centre = destination(&origin, x.atan2(y), x.hypot(y));
+    let radius = locations
+        .iter()
+        .map(|location| calculate_distance(&centre, location))
+        .fold(0.0, f64::max);
+
+    Some((centre, radius))
+}
+
+/// Calculate the smallest bounding box containing a set of locations.
+///
+/// If the locations are closer together going across the antimeridian, the box spans it
+/// and `max_lon` is greater than 180, so `min_lon` is always less than or equal to `max_lon`.
+///
+/// # Arguments
+///
+/// * `locations` - An iterator of references to `GeoLocation` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the `BoundingBox`, or `None` if there are no locations.
+pub fn bounding_box_of<'a>(
+    locations: impl IntoIterator<Item = &'a GeoLocation>,
+) -> Option<BoundingBox> {
+    let mut min_lat = f64::INFINITY;
+    let mut max_lat = f64::NEG_INFINITY;
+    let mut longitudes = Vec::new();
+
+    for location in locations {
+        min_lat = min_lat.min(location.latitude);
+        max_lat = max_lat.max(location.latitude);
+        longitudes.push(if (-180.0..=180.0).contains(&location.longitude) {
+            location.longitude
+        } else {
+            (location.longitude + 180.0).rem_euclid(360.0) - 180.0
+        });
+    }
+
+    if longitudes.is_empty() {
+        return None;
+    }
+    longitudes.sort_by(f64::total_cmp);
+
+    // the box is everything except the largest gap between neighbouring longitudes,
+    // which starts out as the gap across the antimeridian
+    let (mut min_lon, mut max_lon) = (longitudes[0], longitudes[longitudes.len() - 1]);
+    let mut largest_gap = min_lon + 360.0 - max_lon;
+    for pair in longitudes.windows(2) {
+        let gap = pair[1] - pair[0];
+        if gap > largest_gap {
+            largest_gap = gap;
+            min_lon = pair[1];
+            max_lon = pair[0] + 360.0;
+        }
+    }
+
+    Some(BoundingBox {
+        min_lat,
+        max_lat,
+        min_lon,
+        max_lon,
+    })
 }
 
 /// Check if a location is within a bounding box.
 ///
+/// A box spanning the antimeridian, such as one from `BoundingBox::new` or `bounding_box_of`,
+/// has a `min_lon` below -180 or a `max_lon` above 180; the location is then also checked one
+/// full turn east or west so longitudes on either side of the antimeridian are inside it.
+///
 /// # Arguments
 ///
 /// * `location` - A `Location` struct representing the location.
@@ -69,10 +698,14 @@ pub fn calculate_distance(location_1: &GeoLocation, location_2: &GeoLocation) ->
 ///
 /// A `bool` indicating whether the location is within the bounding box.
 pub fn is_within_bounding_box(location: &GeoLocation, bounding_box: &BoundingBox) -> bool {
+    let longitude_within =
+        |longitude: f64| longitude >= bounding_box.min_lon && longitude <= bounding_box.max_lon;
+
     location.latitude >= bounding_box.min_lat
         && location.latitude <= bounding_box.max_lat
-        && location.longitude >= bounding_box.min_lon
-        && location.longitude <= bounding_box.max_lon
+        && (longitude_within(location.longitude)
+            || (bounding_box.max_lon > 180.0 && longitude_within(location.longitude + 360.0))
+            || (bounding_box.min_lon < -180.0 && longitude_within(location.longitude - 360.0)))
 }
 
 #[cfg(test)]
@@ -96,4 +729,446 @@ mod tests {
 
         assert!((distance - 13826.0).abs() < 1.0);
     }
+
+    #[test_log::test]
+    fn test_calculate_distance_with_radius() {
+        let location_1 = GeoLocation {
+            latitude: 0.0,
+            longitude: 0.0,
+        };
+        let location_2 = GeoLocation {
+            latitude: 0.0,
+            longitude: 90.0,
+        };
+
+        assert_eq!(
+            calculate_distance(&location_1, &location_2),
+            calculate_distance_with_radius(&location_1, &location_2, EARTH_RADIUS)
+        );
+
+        let distance =
+            calculate_distance_with_radius(&location_1, &location_2, EARTH_RADIUS_EQUATORIAL);
+        assert!((distance - EARTH_RADIUS_EQUATORIAL * std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    }
+
+    #[test_log::test]
+    fn test_calculate_distance_fast() {
+        for (location_1, location_2) in [
+            (
+                GeoLocation::from((51.7923246977375, 0.629834723775309)),
+                GeoLocation::from((51.735586, 0.468549)),
+            ),
+            (
+                GeoLocation::from((-17.0, 179.5)),
+                GeoLocation::from((-17.5, -179.5)),
+            ),
+        ] {
+            let exact = calculate_distance(&location_1, &location_2);
+            let fast = calculate_distance_fast(&location_1, &location_2);
+            assert!((fast - exact).abs() / exact < 0.001);
+        }
+    }
+
+    #[test_log::test]
+    fn test_initial_bearing() {
+        let origin = GeoLocation::from((0.0, 0.0));
+
+        assert!(initial_bearing(&origin, &GeoLocation::from((1.0, 0.0))).abs() < 1e-9);
+        assert!((initial_bearing(&origin, &GeoLocation::from((0.0, 1.0))) - 90.0).abs() < 1e-9);
+        assert!((initial_bearing(&origin, &GeoLocation::from((-1.0, 0.0))) - 180.0).abs() < 1e-9);
+        assert!((initial_bearing(&origin, &GeoLocation::from((0.0, -1.0))) - 270.0).abs() < 1e-9);
+    }
+
+    #[test_log::test]
+    fn test_cross_and_along_track_distance() {
+        // a path heading east along the equator
+        let start = GeoLocation::from((0.0, 0.0));
+        let end = GeoLocation::from((0.0, 10.0));
+        let one_degree = EARTH_RADIUS.to_radians();
+
+        let north = GeoLocation::from((1.0, 5.0));
+        assert!((cross_track_distance(&north, &start, &end) + one_degree).abs() < 1e-6);
+        assert!((along_track_distance(&north, &start, &end) - 5.0 * one_degree).abs() < 1e-6);
+
+        let south = GeoLocation::from((-1.0, 5.0));
+        assert!((cross_track_distance(&south, &start, &end) - one_degree).abs() < 1e-6);
+
+        let behind = GeoLocation::from((0.0, -2.0));
+        assert!(cross_track_distance(&behind, &start, &end).abs() < 1e-6);
+        assert!((along_track_distance(&behind, &start, &end) + 2.0 * one_degree).abs() < 1e-6);
+    }
+
+    #[test_log::test]
+    fn test_bounding_box_with_dimensions() {
+        let centre = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+
+        let bounds = BoundingBox::with_dimensions(&centre, 20.0, 5.0);
+        let north = GeoLocation {
+            latitude: bounds.max_lat,
+            longitude: centre.longitude,
+        };
+        let east = GeoLocation {
+            latitude: centre.latitude,
+            longitude: bounds.max_lon,
+        };
+        assert!((calculate_distance(&centre, &north) - 10.0).abs() < 0.01);
+        assert!((calculate_distance(&centre, &east) - 2.5).abs() < 0.01);
+
+        // a square box matches one built from a threshold
+        let square = BoundingBox::with_dimensions(&centre, 20.0, 20.0);
+        let threshold = BoundingBox::new(&centre, 10.0);
+        assert!((square.min_lat - threshold.min_lat).abs() < 1e-9);
+        assert!((square.max_lon - threshold.max_lon).abs() < 1e-9);
+    }
+
+    #[test_log::test]
+    fn test_midpoint_and_interpolate() {
+        let witham = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let new_york = GeoLocation {
+            latitude: 40.71,
+            longitude: -74.01,
+        };
+
+        let middle = midpoint(&witham, &new_york);
+        let total = calculate_distance(&witham, &new_york);
+        assert!((calculate_distance(&witham, &middle) - total / 2.0).abs() < 0.001);
+        assert!((calculate_distance(&middle, &new_york) - total / 2.0).abs() < 0.001);
+
+        let halfway = interpolate(&witham, &new_york, 0.5);
+        assert!(calculate_distance(&halfway, &middle) < 0.001);
+
+        let quarter = interpolate(&witham, &new_york, 0.25);
+        assert!((calculate_distance(&witham, &quarter) - total / 4.0).abs() < 0.001);
+        assert!(calculate_distance(&interpolate(&witham, &new_york, 0.0), &witham) < 0.001);
+        assert!(calculate_distance(&interpolate(&witham, &new_york, 1.0), &new_york) < 0.001);
+
+        // the path crosses the antimeridian the short way
+        let fiji = GeoLocation::from((-17.7, 178.0));
+        let samoa = GeoLocation::from((-13.8, -172.1));
+        assert!(midpoint(&fiji, &samoa).longitude.abs() > 170.0);
+        assert!(interpolate(&fiji, &samoa, 0.5).longitude.abs() > 170.0);
+
+        assert_eq!(interpolate(&witham, &witham, 0.5), witham);
+    }
+
+    #[test_log::test]
+    fn test_bounding_box_distance_to() {
+        let bounding_box = BoundingBox {
+            min_lat: 51.0,
+            max_lat: 52.0,
+            min_lon: 0.0,
+            max_lon: 1.0,
+        };
+
+        let inside = GeoLocation {
+            latitude: 51.5,
+            longitude: 0.5,
+        };
+        assert_eq!(bounding_box.distance_to(&inside), 0.0);
+
+        let north = GeoLocation {
+            latitude: 53.0,
+            longitude: 0.5,
+        };
+        assert!((bounding_box.distance_to(&north) - 111.19).abs() < 0.1);
+
+        let corner = GeoLocation {
+            latitude: 50.0,
+            longitude: -1.0,
+        };
+        let expected = calculate_distance(
+            &corner,
+            &GeoLocation {
+                latitude: 51.0,
+                longitude: 0.0,
+            },
+        );
+        assert!((bounding_box.distance_to(&corner) - expected).abs() < 1e-9);
+    }
+
+    #[test_log::test]
+    fn test_bounding_box_area_km2() {
+        let equator = BoundingBox {
+            min_lat: 0.0,
+            max_lat: 1.0,
+            min_lon: 0.0,
+            max_lon: 1.0,
+        };
+        assert!((equator.area_km2() - 12_364.0).abs() < 10.0);
+
+        let arctic = BoundingBox {
+            min_lat: 80.0,
+            max_lat: 81.0,
+            min_lon: 0.0,
+            max_lon: 1.0,
+        };
+        assert!(arctic.area_km2() < equator.area_km2() / 5.0);
+
+        // the whole sphere
+        let globe = BoundingBox {
+            min_lat: -90.0,
+            max_lat: 90.0,
+            min_lon: -180.0,
+            max_lon: 180.0,
+        };
+        let surface = 4.0 * std::f64::consts::PI * EARTH_RADIUS * EARTH_RADIUS;
+        assert!((globe.area_km2() - surface).abs() < 1.0);
+    }
+
+    #[test_log::test]
+    fn test_minimum_enclosing_circle() {
+        assert!(minimum_enclosing_circle(&[]).is_none());
+
+        let witham = GeoLocation::from((51.80, 0.64));
+        let (centre, radius) = minimum_enclosing_circle(std::slice::from_ref(&witham)).unwrap();
+        assert!(centre.distance(&witham) < 1e-6);
+        assert!(radius < 1e-6);
+
+        let london = GeoLocation::from((51.50, -0.14));
+        let (centre, radius) = minimum_enclosing_circle(&[witham.clone(), london.clone()]).unwrap();
+        assert!(centre.distance(&midpoint(&witham, &london)) < 0.1);
+        assert!((radius - witham.distance(&london) / 2.0).abs() < 0.1);
+
+        // interior points barely change the circle, only through the projection's origin,
+        // and every point is enclosed
+        let locations = [
+            GeoLocation::from((51.0, 0.0)),
+            GeoLocation::from((51.0, 1.0)),
+            GeoLocation::from((52.0, 0.5)),
+            GeoLocation::from((51.3, 0.5)),
+            GeoLocation::from((51.5, 0.4)),
+        ];
+        let (centre, radius) = minimum_enclosing_circle(&locations).unwrap();
+        let (triangle_centre, triangle_radius) = minimum_enclosing_circle(&locations[..3]).unwrap();
+        assert!(centre.distance(&triangle_centre) < 0.01);
+        assert!((radius - triangle_radius).abs() < 0.01);
+        for location in &locations {
+            assert!(centre.distance(location) <= radius + 1e-9);
+        }
+
+        // the circle wraps across the antimeridian
+        let (centre, radius) = minimum_enclosing_circle(&[
+            GeoLocation::from((0.0, 179.5)),
+            GeoLocation::from((0.0, -179.5)),
+        ])
+        .unwrap();
+        assert!(centre.longitude.abs() > 179.9);
+        assert!(radius < 60.0);
+
+        // sorted input, the worst case for an unshuffled Welzl, still encloses every point
+        let locations: Vec<GeoLocation> = (0..5000)
+            .map(|i| GeoLocation::from((51.0 + i as f64 * 1e-4, 0.5 + (i as f64).sin() * 0.1)))
+            .collect();
+        let (centre, radius) = minimum_enclosing_circle(&locations).unwrap();
+        for location in &locations {
+            assert!(centre.distance(location) <= radius + 1e-9);
+        }
+        let mut reversed = locations.clone();
+        reversed.reverse();
+        let (reversed_centre, reversed_radius) = minimum_enclosing_circle(&reversed).unwrap();
+        assert!(centre.distance(&reversed_centre) < 0.01);
+        assert!((radius - reversed_radius).abs() < 0.01);
+    }
+
+    #[test_log::test]
+    fn test_shuffle() {
+        let mut items: Vec<usize> = (0..100).collect();
+        shuffle(&mut items);
+        assert_ne!(items, (0..100).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..100).collect::<Vec<_>>());
+
+        let mut empty: [usize; 0] = [];
+        shuffle(&mut empty);
+    }
+
+    #[test_log::test]
+    fn test_convex_hull() {
+        let corners = [(51.0, 0.0), (51.0, 1.0), (52.0, 1.0), (52.0, 0.0)];
+        let mut locations: Vec<GeoLocation> = corners
+            .iter()
+            .map(|&corner| GeoLocation::from(corner))
+            .collect();
+        // interior, duplicate and collinear points are not part of the hull
+        locations.push(GeoLocation::from((51.5, 0.5)));
+        locations.push(GeoLocation::from((51.0, 0.0)));
+        locations.push(GeoLocation::from((51.0, 0.5)));
+
+        let hull = convex_hull(&locations);
+        assert_eq!(hull.len(), 4);
+        for corner in corners {
+            assert!(hull.contains(&GeoLocation::from(corner)));
+        }
+
+        // the vertices are in counter-clockwise order
+        let area: f64 = (0..hull.len())
+            .map(|i| {
+                let (a, b) = (&hull[i], &hull[(i + 1) % hull.len()]);
+                a.longitude * b.latitude - b.longitude * a.latitude
+            })
+            .sum();
+        assert!(area > 0.0);
+
+        // the hull wraps across the antimeridian
+        let pacific = [
+            (-1.0, 179.0),
+            (-1.0, -179.0),
+            (1.0, -179.0),
+            (1.0, 179.0),
+            (0.0, 180.0),
+        ];
+        let locations: Vec<GeoLocation> = pacific
+            .iter()
+            .map(|&point| GeoLocation::from(point))
+            .collect();
+        assert_eq!(convex_hull(&locations).len(), 4);
+
+        assert!(convex_hull(&[]).is_empty());
+        assert_eq!(convex_hull(&locations[..1]), locations[..1]);
+    }
+
+    #[test_log::test]
+    fn test_great_circle_path() {
+        let london = GeoLocation {
+            latitude: 51.5074,
+            longitude: -0.1278,
+        };
+        let new_york = GeoLocation {
+            latitude: 40.7128,
+            longitude: -74.0060,
+        };
+
+        let path = great_circle_path(&london, &new_york, 4);
+        assert_eq!(path.len(), 5);
+        assert_eq!(path[0], london);
+        assert_eq!(path[4], new_york);
+        assert!((path[2].latitude - midpoint(&london, &new_york).latitude).abs() < 1e-9);
+        // the great circle bends north of both endpoints
+        assert!(path[1].latitude > london.latitude);
+
+        let step = calculate_distance(&path[0], &path[1]);
+        for pair in path.windows(2) {
+            assert!((calculate_distance(&pair[0], &pair[1]) - step).abs() < 1e-6);
+        }
+
+        assert_eq!(great_circle_path(&london, &new_york, 0), [london, new_york]);
+
+        // crossing the antimeridian is not special-cased
+        let path = great_circle_path(
+            &GeoLocation::from((-17.0, 178.0)),
+            &GeoLocation::from((-17.0, -178.0)),
+            2,
+        );
+        assert!(path[1].longitude.abs() > 179.9);
+    }
+
+    #[test_log::test]
+    fn test_bounding_box_of() {
+        let bounding_box = bounding_box_of(&[
+            GeoLocation {
+                latitude: 51.8,
+                longitude: 0.6,
+            },
+            GeoLocation {
+                latitude: 51.7,
+                longitude: 0.5,
+            },
+        ])
+        .unwrap();
+        assert_eq!((bounding_box.min_lat, bounding_box.max_lat), (51.7, 51.8));
+        assert_eq!((bounding_box.min_lon, bounding_box.max_lon), (0.5, 0.6));
+
+        // points either side of the antimeridian wrap rather than spanning the globe
+        let bounding_box = bounding_box_of(&[
+            GeoLocation {
+                latitude: -17.0,
+                longitude: 179.0,
+            },
+            GeoLocation {
+                latitude: -16.0,
+                longitude: -179.5,
+            },
+        ])
+        .unwrap();
+        assert_eq!(bounding_box.min_lon, 179.0);
+        assert_eq!(bounding_box.max_lon, 180.5);
+
+        assert!(bounding_box_of(&[]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_bounding_box_of_contains_points() {
+        let point = |latitude, longitude| GeoLocation {
+            latitude,
+            longitude,
+        };
+        let point_sets = [
+            vec![point(51.8, 0.6), point(51.7, 0.5), point(51.75, 0.55)],
+            vec![
+                point(-17.0, 179.0),
+                point(-16.0, -179.5),
+                point(-16.5, 180.0),
+            ],
+        ];
+
+        for points in &point_sets {
+            let bounding_box = bounding_box_of(points).unwrap();
+            for location in points {
+                assert!(is_within_bounding_box(location, &bounding_box));
+            }
+        }
+
+        // the wrapped box only reaches as far east as -179.5
+        let bounding_box = bounding_box_of(&point_sets[1]).unwrap();
+        assert!(!is_within_bounding_box(
+            &point(-16.5, -170.0),
+            &bounding_box
+        ));
+        assert!(!is_within_bounding_box(&point(-16.5, 170.0), &bounding_box));
+    }
+
+    #[test_log::test]
+    fn test_spherical_centroid() {
+        let locations = [
+            GeoLocation {
+                latitude: 10.0,
+                longitude: 179.0,
+            },
+            GeoLocation {
+                latitude: -10.0,
+                longitude: -179.0,
+            },
+        ];
+
+        let centroid = spherical_centroid(&locations).unwrap();
+        assert!(centroid.latitude.abs() < 1e-9);
+        assert!((centroid.longitude.abs() - 180.0).abs() < 1e-9);
+
+        assert!(spherical_centroid(&[]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_within_radius() {
+        let location_1 = GeoLocation {
+            latitude: 51.7923246977375,
+            longitude: 0.629834723775309,
+        };
+        let location_2 = GeoLocation {
+            latitude: 51.735586,
+            longitude: 0.468549,
+        };
+
+        let distance = calculate_distance(&location_1, &location_2);
+
+        assert!(within_radius(&location_1, &location_2, distance));
+        assert!(within_radius(&location_1, &location_2, distance + 1.0));
+        assert!(!within_radius(&location_1, &location_2, distance - 1.0));
+        assert!(within_radius(&location_1, &location_1, 0.0));
+    }
 }
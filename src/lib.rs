@@ -1,11 +1,55 @@
-pub use geonames::{get_gazetteer_data, get_postal_data, invalidate_cache};
-pub use haversine::{calculate_distance, BoundingBox};
-pub use models::{Accuracy, Country, Gazetteer, GeoLocation, PostalData};
+#[cfg(feature = "mmap")]
+pub use binary::*;
+pub use error::{GeoError, ParseError};
+#[cfg(any(feature = "csv", feature = "geojson", feature = "serde"))]
+pub use export::*;
+pub use geocoder::Geocoder;
+pub use geonames::{
+    cache_dir, cache_manifest, cache_manifest_with_config, cache_size_bytes,
+    cache_size_bytes_with_config, check_available, check_available_with_config,
+    dedup_by_postal_code, fetch_raw, fetch_raw_with_config, fetch_raw_with_status,
+    for_each_postal_record, for_each_postal_record_from_reader, get_alternate_names_data,
+    get_alternate_names_data_with_config, get_gazetteer_data, get_gazetteer_data_filtered,
+    get_gazetteer_data_with_config, get_postal_data, get_postal_data_checked,
+    get_postal_data_with_config, get_postal_summaries, invalidate_cache,
+    invalidate_cache_with_config, invalidate_country, invalidate_country_with_config,
+    list_cached_countries, list_cached_countries_with_config, load_alternate_names_data,
+    load_alternate_names_data_from_path, load_gazetteer_data, load_gazetteer_data_filtered,
+    load_gazetteer_data_from_path, load_postal_data, load_postal_data_dedup,
+    load_postal_data_from_path, load_postal_data_with_schema, load_postal_summaries,
+    parse_postal_line, parse_postal_line_with_schema, read_data_file, require_version,
+    require_version_with_config, stream_all_countries_postal,
+    stream_all_countries_postal_with_config, try_load_postal_data,
+    try_load_postal_data_with_schema, try_load_postal_summaries, warm_cache,
+    warm_cache_with_config, CacheManifest, Data, DownloadConfig, LoadResult, ManifestEntry,
+    PostalSchema, DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT, MANIFEST_FILE_NAME,
+};
+pub use haversine::{
+    along_track_distance, bounding_box_of, calculate_distance, calculate_distance_fast,
+    calculate_distance_with_radius, convex_hull, cross_track_distance, great_circle_path,
+    initial_bearing, interpolate, midpoint, minimum_enclosing_circle, spherical_centroid,
+    within_radius, BoundingBox, EARTH_RADIUS, EARTH_RADIUS_AUTHALIC, EARTH_RADIUS_EQUATORIAL,
+    EARTH_RADIUS_MEAN, EARTH_RADIUS_POLAR,
+};
+pub use models::{
+    Accuracy, AlternateName, CompassDirection, Country, CountryCode, Gazetteer, GeoLocation,
+    Geolocatable, PostalData, PostalSummary,
+};
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
 pub use utils::*;
 
+#[cfg(feature = "mmap")]
+mod binary;
+mod error;
+#[cfg(any(feature = "csv", feature = "geojson", feature = "serde"))]
+mod export;
+mod geocoder;
 mod geonames;
 mod haversine;
 mod models;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 mod utils;
 
 #[cfg(test)]
@@ -17,12 +61,79 @@ mod tests {
     static GEONAMES_POSTAL_DATA: once_cell::sync::Lazy<Vec<PostalData>> =
         once_cell::sync::Lazy::new(|| get_postal_data(Country::All));
 
+    #[allow(clippy::let_and_return)]
     static GEONAMES_GAZETTEER_DATA: once_cell::sync::Lazy<Vec<Gazetteer>> =
         once_cell::sync::Lazy::new(|| {
             let geonames_data = crate::geonames::get_gazetteer_data(Country::GreatBritain);
             geonames_data
         });
 
+    /// Parse three Essex and London postal records with their admin fields filled in.
+    #[cfg(any(
+        feature = "csv",
+        feature = "mmap",
+        feature = "serde",
+        feature = "sqlite"
+    ))]
+    pub(crate) fn essex_postal_fixture() -> Vec<PostalData> {
+        load_postal_data(
+            "GB\tCM8\tWitham\tEngland\tENG\tEssex\t11E\tBraintree District\t22UC\t51.8\t0.63\t6\n\
+             GB\tCM9\tMaldon\tEngland\tENG\tEssex\t11E\tMaldon District\t22UH\t51.7\t0.7\t\n\
+             GB\tSW1A\tLondon\tEngland\tENG\tGreater London\t\t\t\t51.5\t-0.14\t4\n",
+        )
+    }
+
+    /// Get a path in the temporary directory that is unique to this test process.
+    #[cfg(any(feature = "mmap", feature = "sqlite"))]
+    pub(crate) fn temp_path(file_name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("geo_rust_{}_{}", std::process::id(), file_name))
+    }
+
+    fn postal_fixture(postal_code: &str, latitude: f64, longitude: f64) -> PostalData {
+        PostalData {
+            country_code: "GB".to_string(),
+            postal_code: postal_code.to_string(),
+            place_name: None,
+            admin_name1: None,
+            admin_code1: None,
+            admin_name2: None,
+            admin_code2: None,
+            admin_name3: None,
+            admin_code3: None,
+            geolocation: Some(GeoLocation {
+                latitude,
+                longitude,
+            }),
+            accuracy: Accuracy::NoAccuracyData,
+        }
+    }
+
+    fn gazetteer_fixture(id: i64, name: &str, latitude: f64, longitude: f64) -> Gazetteer {
+        Gazetteer {
+            id,
+            name: name.to_string(),
+            asciiname: name.to_string(),
+            alternate_names: Vec::new(),
+            geolocation: Some(GeoLocation {
+                latitude,
+                longitude,
+            }),
+            feature_class: "P".to_string(),
+            feature_code: "PPL".to_string(),
+            country_code: "GB".to_string(),
+            alternate_country_codes: Vec::new(),
+            admin1_code: None,
+            admin2_code: None,
+            admin3_code: None,
+            admin4_code: None,
+            population: 0,
+            elevation: 0,
+            dem: 0,
+            timezone: "Europe/London".to_string(),
+            modification_date: chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        }
+    }
+
     #[test_log::test]
     fn test_get_nearest_postcode() {
         let location = GeoLocation {
@@ -37,6 +148,30 @@ mod tests {
         assert_eq!(nearest_postcode.postal_code, "CM8");
     }
 
+    #[test_log::test]
+    fn test_try_get_nearest_postcode() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+
+        assert!(matches!(
+            try_get_nearest_postcode(location.clone(), &[]),
+            Err(GeoError::EmptyDataset)
+        ));
+
+        let mut unlocated = postal_fixture("CM8", 51.80, 0.64);
+        unlocated.geolocation = None;
+        assert!(matches!(
+            try_get_nearest_postcode(location.clone(), &[unlocated.clone()]),
+            Err(GeoError::NoGeolocatedRecords)
+        ));
+
+        let geonames_data = vec![unlocated, postal_fixture("CM9", 51.73, 0.68)];
+        let nearest_postcode = try_get_nearest_postcode(location, &geonames_data).unwrap();
+        assert_eq!(nearest_postcode.postal_code, "CM9");
+    }
+
     #[test_log::test]
     fn test_get_nearest_postcode_with_bounding() {
         let location = GeoLocation {
@@ -63,6 +198,151 @@ mod tests {
         assert!((location.longitude - 0.629834723775309).abs() < 0.1);
     }
 
+    #[test_log::test]
+    fn test_distance_between_postcodes() {
+        let mut unlocated = postal_fixture("CO1", 0.0, 0.0);
+        unlocated.geolocation = None;
+        let geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("SW1A", 51.50, -0.14),
+            unlocated,
+        ];
+
+        let distance = distance_between_postcodes("CM8", "SW1A", &geonames_data).unwrap();
+        assert!((distance - 63.0).abs() < 1.0);
+        assert_eq!(
+            distance_between_postcodes("SW1A", "CM8", &geonames_data),
+            Some(distance)
+        );
+        assert!(distance_between_postcodes("CM8", "CO1", &geonames_data).is_none());
+        assert!(distance_between_postcodes("CM8", "XX1", &geonames_data).is_none());
+    }
+
+    #[test_log::test]
+    fn test_density_grid() {
+        let geonames_data = vec![
+            postal_fixture("CM8 1", 51.9, 0.1),
+            postal_fixture("CM8 2", 51.8, 0.2),
+            postal_fixture("CM9 4", 51.1, 0.9),
+            postal_fixture("CM9 5", 51.0, 1.0),
+            postal_fixture("SW1A 1", 51.5, -0.1),
+        ];
+        let bounds = BoundingBox {
+            min_lat: 51.0,
+            max_lat: 52.0,
+            min_lon: 0.0,
+            max_lon: 1.0,
+        };
+
+        assert_eq!(
+            density_grid(&geonames_data, &bounds, 2, 2),
+            [[2, 0], [0, 2]]
+        );
+        assert!(density_grid(&geonames_data, &bounds, 0, 2)
+            .iter()
+            .all(Vec::is_empty));
+
+        // a box across the antimeridian counts points on both sides
+        let geonames_data = vec![
+            postal_fixture("A", -16.5, 179.5),
+            postal_fixture("B", -16.5, -179.5),
+        ];
+        let bounds = BoundingBox {
+            min_lat: -17.0,
+            max_lat: -16.0,
+            min_lon: 179.0,
+            max_lon: 181.0,
+        };
+        assert_eq!(density_grid(&geonames_data, &bounds, 2, 1), [[1, 1]]);
+    }
+
+    #[test_log::test]
+    fn test_expand_partial_postcode() {
+        let mut geonames_data = vec![
+            postal_fixture("CM8 2", 51.80, 0.64),
+            postal_fixture("CM8 1", 51.79, 0.63),
+            postal_fixture("CM9 4", 51.73, 0.68),
+            postal_fixture("CM8 3", 51.81, 0.65),
+        ];
+        geonames_data[3].accuracy = Accuracy::Centroid;
+
+        let postcodes: Vec<&str> = expand_partial_postcode(" cm8", &geonames_data)
+            .iter()
+            .map(|geoname| geoname.postal_code.as_str())
+            .collect();
+        assert_eq!(postcodes, ["CM8 3", "CM8 1", "CM8 2"]);
+        assert!(expand_partial_postcode("SW1", &geonames_data).is_empty());
+
+        let geonames_data = vec![
+            postal_fixture("SW1A 1", 51.50, -0.14),
+            postal_fixture("SW10 0", 51.48, -0.18),
+            postal_fixture("SW19 1", 51.42, -0.20),
+            postal_fixture("SW2 1", 51.45, -0.12),
+        ];
+        let postcodes = |partial| -> Vec<&str> {
+            expand_partial_postcode(partial, &geonames_data)
+                .iter()
+                .map(|geoname| geoname.postal_code.as_str())
+                .collect()
+        };
+        // a plain prefix match, so SW1 takes in SW10 to SW19 as well
+        assert_eq!(postcodes("SW1"), ["SW10 0", "SW19 1", "SW1A 1"]);
+        assert_eq!(postcodes("SW1A"), ["SW1A 1"]);
+    }
+
+    #[test_log::test]
+    fn test_postcodes_with_prefix() {
+        let geonames_data = vec![
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM8", 51.80, 0.65),
+            postal_fixture("CO1", 51.89, 0.90),
+        ];
+
+        assert_eq!(postcodes_with_prefix(" cm", &geonames_data), ["CM8", "CM9"]);
+        assert!(postcodes_with_prefix("SW", &geonames_data).is_empty());
+    }
+
+    #[test_log::test]
+    fn test_postcodes_within_radii() {
+        let geonames_data = vec![
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CO1", 51.89, 0.90),
+            postal_fixture("CM8", 51.80, 0.65),
+            postal_fixture("SW1A", 51.50, -0.14),
+        ];
+        let centres = [
+            (GeoLocation::from((51.79, 0.63)), 10.0),
+            (GeoLocation::from((51.80, 0.64)), 5.0),
+            (GeoLocation::from((51.89, 0.91)), 1.0),
+        ];
+
+        assert_eq!(
+            postcodes_within_radii(&centres, &geonames_data),
+            ["CM9", "CM8", "CO1"]
+        );
+        assert!(postcodes_within_radii(&[], &geonames_data).is_empty());
+    }
+
+    #[test_log::test]
+    fn test_postal_code_centroid() {
+        let mut unlocated = postal_fixture("CM8", 0.0, 0.0);
+        unlocated.geolocation = None;
+        let geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.60),
+            unlocated,
+            postal_fixture("CM8", 51.80, 0.70),
+            postal_fixture("CM9", 51.70, 0.68),
+        ];
+
+        let centroid = postal_code_centroid("CM8", &geonames_data).unwrap();
+        assert!((centroid.latitude - 51.80).abs() < 0.001);
+        assert!((centroid.longitude - 0.65).abs() < 0.001);
+
+        assert!(postal_code_centroid("SW1A", &geonames_data).is_none());
+    }
+
     #[test_log::test]
     fn test_get_postcodes_within_radius() {
         let location = GeoLocation {
@@ -135,6 +415,539 @@ mod tests {
         assert_eq!(nearest_place.name, "Witham Blunts Hall");
     }
 
+    #[test_log::test]
+    fn test_get_nearest_place_matching() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let geonames_data = vec![
+            gazetteer_fixture(1, "Witham", 51.80, 0.64),
+            gazetteer_fixture(2, "Maldon", 51.73, 0.68),
+            gazetteer_fixture(3, "Wickham Bishops", 51.78, 0.67),
+        ];
+
+        let nearest_place = get_nearest_place_matching(location.clone(), &geonames_data, |place| {
+            place.name.contains("ham")
+        })
+        .unwrap();
+        assert_eq!(nearest_place.name, "Witham");
+
+        let nearest_place = get_nearest_place_matching(location.clone(), &geonames_data, |place| {
+            place.name.starts_with('M')
+        })
+        .unwrap();
+        assert_eq!(nearest_place.name, "Maldon");
+
+        assert!(get_nearest_place_matching(location, &geonames_data, |_| false).is_none());
+    }
+
+    #[test_log::test]
+    fn test_get_nearest_place_excluding() {
+        let geonames_data = vec![
+            gazetteer_fixture(1, "Witham", 51.80, 0.64),
+            gazetteer_fixture(2, "Maldon", 51.73, 0.68),
+            gazetteer_fixture(3, "London", 51.51, -0.13),
+        ];
+        let witham = geonames_data[0].geolocation.clone().unwrap();
+
+        assert_eq!(
+            get_nearest_place(witham.clone(), &geonames_data)
+                .unwrap()
+                .id,
+            1
+        );
+        assert_eq!(
+            get_nearest_place_excluding(witham, &geonames_data, 1)
+                .unwrap()
+                .name,
+            "Maldon"
+        );
+        assert!(
+            get_nearest_place_excluding(GeoLocation::from((0.0, 0.0)), &geonames_data[..1], 1)
+                .is_none()
+        );
+    }
+
+    #[test_log::test]
+    fn test_nearest_place_detailed() {
+        let geonames_data = vec![
+            gazetteer_fixture(1, "Witham", 51.80, 0.64),
+            gazetteer_fixture(2, "Maldon", 51.73, 0.68),
+        ];
+        let location = GeoLocation::from((51.78, 0.62));
+
+        let nearest = nearest_place_detailed(location.clone(), &geonames_data).unwrap();
+        assert_eq!(nearest.place.name, "Witham");
+        assert!(
+            (nearest.distance_km - location.distance(&GeoLocation::from((51.80, 0.64)))).abs()
+                < 1e-9
+        );
+        assert!(nearest.bearing_deg > 0.0 && nearest.bearing_deg < 90.0);
+        assert!(nearest_place_detailed(location, &[]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_get_timezone_at() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+
+        let mut hamlet = gazetteer_fixture(1, "Hamlet", 51.79, 0.63);
+        hamlet.timezone = "Europe/Hamlet".to_string();
+        let mut town = gazetteer_fixture(2, "Town", 51.80, 0.70);
+        town.population = 25000;
+        let mut far_city = gazetteer_fixture(3, "City", 52.5, 0.63);
+        far_city.population = 1000000;
+        far_city.timezone = "Europe/City".to_string();
+        let mut feature = gazetteer_fixture(4, "Hill", 51.79, 0.63);
+        feature.feature_class = "T".to_string();
+        feature.timezone = "Europe/Hill".to_string();
+
+        let geonames_data = vec![hamlet, town, far_city, feature];
+
+        assert_eq!(
+            get_timezone_at(location.clone(), &geonames_data),
+            Some("Europe/London")
+        );
+        assert_eq!(get_timezone_at(location, &geonames_data[3..]), None);
+    }
+
+    #[test_log::test]
+    fn test_nearest_city() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+
+        let mut suburb = gazetteer_fixture(1, "Suburb", 51.791, 0.631);
+        suburb.population = 500;
+        let mut city = gazetteer_fixture(2, "City", 51.85, 0.70);
+        city.population = 250000;
+        city.feature_code = "PPLA".to_string();
+        let mut region = gazetteer_fixture(3, "Region", 51.79, 0.63);
+        region.population = 1000000;
+        region.feature_class = "A".to_string();
+        region.feature_code = "ADM2".to_string();
+        let mut far_city = gazetteer_fixture(4, "Far City", 53.0, 0.63);
+        far_city.population = 10000000;
+
+        let geonames_data = vec![suburb, city, region, far_city];
+
+        let city = nearest_city(location.clone(), &geonames_data, 50.0).unwrap();
+        assert_eq!(city.name, "City");
+
+        assert!(nearest_city(location, &geonames_data, 0.01).is_none());
+    }
+
+    #[test_log::test]
+    fn test_get_n_nearest_places() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let mut hill = gazetteer_fixture(4, "Hill", 51.791, 0.63);
+        hill.feature_class = "T".to_string();
+        let geonames_data = vec![
+            gazetteer_fixture(1, "Maldon", 51.73, 0.68),
+            gazetteer_fixture(2, "Witham", 51.80, 0.64),
+            gazetteer_fixture(3, "Chelmsford", 51.73, 0.47),
+            hill,
+        ];
+
+        let names = |places: Vec<&Gazetteer>| {
+            places
+                .iter()
+                .map(|place| place.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(get_n_nearest_places(location.clone(), &geonames_data, 2)),
+            ["Hill", "Witham"]
+        );
+        assert_eq!(
+            names(get_n_nearest_places_matching(
+                location.clone(),
+                &geonames_data,
+                5,
+                |place| place.feature_class == "P"
+            )),
+            ["Witham", "Maldon", "Chelmsford"]
+        );
+        assert!(get_n_nearest_places(location, &geonames_data, 0).is_empty());
+    }
+
+    #[test_log::test]
+    fn test_get_nearest_postcode_within() {
+        let location = GeoLocation {
+            latitude: 51.0,
+            longitude: 0.0,
+        };
+        // inside the 10km bounding box, but about 13km away in its corner
+        let geonames_data = vec![postal_fixture("CM8", 51.0899, 0.1428)];
+
+        assert!(
+            get_nearest_postcode_with_bounding(location.clone(), &geonames_data, 10.0).is_some()
+        );
+        assert!(get_nearest_postcode_within(location.clone(), &geonames_data, 10.0).is_none());
+        assert!(get_nearest_postcode_within(location, &geonames_data, 15.0).is_some());
+    }
+
+    #[test_log::test]
+    fn test_nearest_among() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("SW1A", 51.50, -0.14),
+        ];
+
+        let nearest = nearest_among(location.clone(), &["SW1A", "CM9"], &geonames_data);
+        assert_eq!(nearest.unwrap().postal_code, "CM9");
+        assert!(nearest_among(location.clone(), &[], &geonames_data).is_none());
+        assert!(nearest_among(location, &["XX1"], &geonames_data).is_none());
+    }
+
+    #[test_log::test]
+    fn test_nearest_postcode_excluding() {
+        let geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM8", 51.78, 0.66),
+            postal_fixture("CM9", 51.73, 0.68),
+        ];
+        let location = GeoLocation::from((51.79, 0.63));
+
+        assert_eq!(
+            nearest_postcode_excluding(location.clone(), &geonames_data, "CM8")
+                .unwrap()
+                .postal_code,
+            "CM9"
+        );
+        assert!(nearest_postcode_excluding(location, &geonames_data[..2], "CM8").is_none());
+    }
+
+    #[test_log::test]
+    fn test_nearest_postcode_group() {
+        let mut geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("CM8", 51.78, 0.66),
+            postal_fixture("CM8", 48.85, 2.35),
+        ];
+        geonames_data[0].place_name = Some("Witham".to_string());
+        geonames_data[2].place_name = Some("Rivenhall".to_string());
+        geonames_data[3].country_code = "FR".to_string();
+
+        let group =
+            nearest_postcode_group(GeoLocation::from((51.79, 0.63)), &geonames_data).unwrap();
+        let places: Vec<&str> = group
+            .iter()
+            .filter_map(|geoname| geoname.place_name.as_deref())
+            .collect();
+        assert_eq!(places, ["Witham", "Rivenhall"]);
+        assert!(nearest_postcode_group(GeoLocation::from((0.0, 0.0)), &[]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_snap_to_postcode() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let geonames_data = vec![
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("CM8", 51.80, 0.64),
+        ];
+
+        let (postcode, snapped) = snap_to_postcode(location, &geonames_data).unwrap();
+        assert_eq!(postcode, "CM8");
+        assert_eq!(snapped, GeoLocation::from((51.80, 0.64)));
+
+        assert!(snap_to_postcode(GeoLocation::from((0.0, 0.0)), &[]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_admin_hierarchy_at() {
+        let mut witham = postal_fixture("CM8", 51.80, 0.64);
+        witham.admin_name1 = Some("England".to_string());
+        witham.admin_code1 = Some("ENG".to_string());
+        witham.admin_name2 = Some("Essex".to_string());
+        let geonames_data = vec![postal_fixture("SW1A", 51.50, -0.14), witham];
+
+        assert_eq!(
+            admin_hierarchy_at(GeoLocation::from((51.79, 0.63)), &geonames_data),
+            Some(AdminHierarchy {
+                admin_name1: Some("England"),
+                admin_code1: Some("ENG"),
+                admin_name2: Some("Essex"),
+                admin_code2: None,
+                admin_name3: None,
+                admin_code3: None,
+            })
+        );
+        assert!(admin_hierarchy_at(GeoLocation::from((0.0, 0.0)), &[]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_geocoder() {
+        let geocoder = Geocoder::from_data(
+            vec![
+                postal_fixture("CM8", 51.80, 0.64),
+                postal_fixture("SW1A", 51.50, -0.14),
+            ],
+            vec![
+                gazetteer_fixture(1, "Witham", 51.80, 0.64),
+                gazetteer_fixture(2, "London", 51.51, -0.13),
+            ],
+        );
+        let location = GeoLocation::from((51.79, 0.63));
+
+        assert_eq!(geocoder.postal().len(), 2);
+        assert_eq!(geocoder.gazetteer().len(), 2);
+        assert_eq!(
+            geocoder
+                .nearest_postcode(location.clone())
+                .unwrap()
+                .postal_code,
+            "CM8"
+        );
+        assert_eq!(
+            geocoder.nearest_place(location.clone()).unwrap().name,
+            "Witham"
+        );
+        assert_eq!(
+            geocoder.postcodes_within_radius(location.clone(), 10.0),
+            ["CM8"]
+        );
+        assert_eq!(
+            geocoder.places_within_radius(location.clone(), 10.0),
+            ["Witham"]
+        );
+        assert_eq!(geocoder.country_at(location), Some("GB"));
+        assert_eq!(
+            geocoder.postcode_location("SW1A"),
+            Some(GeoLocation::from((51.50, -0.14)))
+        );
+        assert_eq!(
+            geocoder.place_location("London"),
+            Some(GeoLocation::from((51.51, -0.13)))
+        );
+        assert!(Geocoder::default()
+            .nearest_postcode(GeoLocation::from((0.0, 0.0)))
+            .is_none());
+    }
+
+    #[test_log::test]
+    fn test_country_at() {
+        let mut calais = postal_fixture("62100", 50.95, 1.86);
+        calais.country_code = "FR".to_string();
+        let geonames_data = vec![postal_fixture("CT16", 51.13, 1.31), calais];
+
+        assert_eq!(
+            country_at(GeoLocation::from((51.1, 1.3)), &geonames_data),
+            Some("GB")
+        );
+        assert_eq!(
+            country_at(GeoLocation::from((50.9, 1.9)), &geonames_data),
+            Some("FR")
+        );
+        assert!(country_at(GeoLocation::from((0.0, 0.0)), &[]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_nearest_pair() {
+        let location = GeoLocation::from((51.0, 0.0));
+        let geonames_data = vec![
+            postal_fixture("EAST", 51.0, 0.02),
+            postal_fixture("NORTH", 51.02, 0.0),
+            postal_fixture("WEST", 51.0, -0.05),
+        ];
+
+        let (nearest, opposite) = nearest_pair(location.clone(), &geonames_data).unwrap();
+        assert_eq!(nearest.postal_code, "EAST");
+        assert_eq!(opposite.postal_code, "WEST");
+
+        assert!(nearest_pair(location, &geonames_data[..2]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_reverse_geocode_batch() {
+        let geonames_data = vec![
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("SW1A", 51.50, -0.14),
+        ];
+        let points = [
+            GeoLocation::from((51.51, -0.13)),
+            GeoLocation::from((51.79, 0.63)),
+            GeoLocation::from((51.72, 0.69)),
+            // New Zealand, on the other side of the world from every record
+            GeoLocation::from((-40.0, 170.0)),
+        ];
+
+        let postcodes: Vec<&str> = reverse_geocode_batch(&points, &geonames_data)
+            .into_iter()
+            .map(|postcode| postcode.unwrap().postal_code.as_str())
+            .collect();
+        assert_eq!(postcodes[..3], ["SW1A", "CM8", "CM9"]);
+        assert_eq!(
+            postcodes[3],
+            get_nearest_postcode(points[3].clone(), &geonames_data)
+                .unwrap()
+                .postal_code
+        );
+
+        assert_eq!(
+            reverse_geocode_batch(&points, &[]),
+            [None, None, None, None]
+        );
+    }
+
+    #[test_log::test]
+    fn test_all_postal_by_distance() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let mut unlocated = postal_fixture("XX1", 0.0, 0.0);
+        unlocated.geolocation = None;
+        let geonames_data = vec![
+            postal_fixture("SW1A", 51.50, -0.14),
+            unlocated,
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM9", 51.73, 0.68),
+        ];
+
+        let postcodes: Vec<&str> = all_postal_by_distance(location.clone(), &geonames_data)
+            .iter()
+            .map(|(geoname, _)| geoname.postal_code.as_str())
+            .collect();
+        assert_eq!(postcodes, ["CM8", "CM9", "SW1A"]);
+
+        let places = vec![
+            gazetteer_fixture(1, "London", 51.51, -0.13),
+            gazetteer_fixture(2, "Witham", 51.80, 0.64),
+        ];
+        let (nearest, distance) = all_places_by_distance(location, &places)[0];
+        assert_eq!(nearest.name, "Witham");
+        assert!(distance < 2.0);
+    }
+
+    #[test_log::test]
+    fn test_distinct_admin() {
+        let mut geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("SW1A", 51.50, -0.14),
+            postal_fixture("CF10", 51.48, -3.18),
+            postal_fixture("XX1", 0.0, 0.0),
+        ];
+        for (geoname, (admin1, admin2)) in geonames_data.iter_mut().zip([
+            ("England", "Essex"),
+            ("England", "Essex"),
+            ("England", "Greater London"),
+            ("Wales", "Cardiff"),
+            ("", ""),
+        ]) {
+            geoname.admin_name1 = Some(admin1.to_string());
+            geoname.admin_name2 = Some(admin2.to_string());
+        }
+        geonames_data[4].admin_name2 = None;
+
+        assert_eq!(distinct_admin1(&geonames_data), ["England", "Wales"]);
+        assert_eq!(
+            distinct_admin2(&geonames_data),
+            ["Cardiff", "Essex", "Greater London"]
+        );
+    }
+
+    #[test_log::test]
+    fn test_get_postal_data_in_admin() {
+        let mut geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("SW1A", 51.50, -0.14),
+            postal_fixture("CF10", 51.48, -3.18),
+        ];
+        for (geoname, (admin1, admin2)) in geonames_data.iter_mut().zip([
+            (("England", "ENG"), ("Essex", "E10")),
+            (("England", "ENG"), ("Essex", "E10")),
+            (("England", "ENG"), ("Greater London", "GLA")),
+            (("Wales", "WLS"), ("Cardiff", "W06")),
+        ]) {
+            geoname.admin_name1 = Some(admin1.0.to_string());
+            geoname.admin_code1 = Some(admin1.1.to_string());
+            geoname.admin_name2 = Some(admin2.0.to_string());
+            geoname.admin_code2 = Some(admin2.1.to_string());
+        }
+        let postcodes = |geonames: Vec<&PostalData>| -> Vec<String> {
+            geonames
+                .iter()
+                .map(|geoname| geoname.postal_code.clone())
+                .collect()
+        };
+
+        assert_eq!(
+            postcodes(get_postal_data_in_admin1("england", &geonames_data)),
+            ["CM8", "CM9", "SW1A"]
+        );
+        assert_eq!(get_postal_data_in_admin1("wls", &geonames_data).len(), 1);
+        assert!(get_postal_data_in_admin1("Scotland", &geonames_data).is_empty());
+        assert!(get_postal_data_in_admin1("essex", &geonames_data).is_empty());
+
+        assert_eq!(
+            postcodes(get_postal_data_in_admin2(" essex", &geonames_data)),
+            ["CM8", "CM9"]
+        );
+        assert_eq!(get_postal_data_in_admin2("gla", &geonames_data).len(), 1);
+        assert!(get_postal_data_in_admin2("england", &geonames_data).is_empty());
+    }
+
+    #[test_log::test]
+    fn test_find_inconsistent_postcodes() {
+        let mut elsewhere = postal_fixture("CM8", 40.0, -70.0);
+        elsewhere.country_code = "US".to_string();
+        let geonames_data = vec![
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("CM8", 51.81, 0.65),
+            elsewhere,
+            postal_fixture("CM9", 52.73, 0.68),
+            postal_fixture("CM9", 51.74, 0.68),
+        ];
+
+        let inconsistent = find_inconsistent_postcodes(&geonames_data, 10.0);
+        assert_eq!(inconsistent.len(), 1);
+        assert_eq!(inconsistent[0].0, "CM9");
+        assert!((inconsistent[0].1 - 111.2).abs() < 0.1);
+
+        assert_eq!(find_inconsistent_postcodes(&geonames_data, 0.5).len(), 2);
+    }
+
+    #[test_log::test]
+    fn test_get_n_nearest_postcodes() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let geonames_data = vec![
+            postal_fixture("CM9", 51.73, 0.68),
+            postal_fixture("CM8", 51.80, 0.64),
+            postal_fixture("CM1", 51.73, 0.47),
+        ];
+
+        let postcodes: Vec<&str> = get_n_nearest_postcodes(location, &geonames_data, 2)
+            .iter()
+            .map(|postcode| postcode.postal_code.as_str())
+            .collect();
+        assert_eq!(postcodes, ["CM8", "CM9"]);
+    }
+
     #[test_log::test]
     fn test_get_place_location() {
         let place = "Chelmsford";
@@ -146,6 +959,40 @@ mod tests {
         assert!((location.longitude - 0.468549).abs() < 0.1);
     }
 
+    #[test_log::test]
+    fn test_get_place_location_tie_break() {
+        let mut geonames_data = vec![
+            gazetteer_fixture(1, "Springfield", 39.80, -89.64),
+            gazetteer_fixture(2, "Springfield", 42.10, -72.59),
+            gazetteer_fixture(3, "Springfield", 37.21, -93.29),
+            gazetteer_fixture(4, "Springfield", 44.05, -123.02),
+            gazetteer_fixture(5, "Shelbyville", 39.41, -88.79),
+        ];
+        for (geoname, population) in geonames_data
+            .iter_mut()
+            .zip([114_000, 155_000, 169_000, 169_000, 0])
+        {
+            geoname.population = population;
+        }
+
+        assert_eq!(
+            get_place_location("Springfield", &geonames_data),
+            geonames_data[2].geolocation
+        );
+
+        let ids: Vec<i64> = get_place_location_all("Springfield", &geonames_data)
+            .iter()
+            .map(|geoname| geoname.id)
+            .collect();
+        assert_eq!(ids, [3, 4, 2, 1]);
+
+        geonames_data[2].geolocation = None;
+        assert_eq!(
+            get_place_location("Springfield", &geonames_data),
+            geonames_data[3].geolocation
+        );
+    }
+
     #[test_log::test]
     fn test_get_places_within_radius() {
         let location = GeoLocation {
@@ -172,6 +1019,103 @@ mod tests {
         }
     }
 
+    #[test_log::test]
+    fn test_places_within_radius_by_feature() {
+        let mut geonames_data = vec![
+            gazetteer_fixture(1, "Witham", 51.80, 0.64),
+            gazetteer_fixture(2, "Chelmsford", 51.73, 0.47),
+            gazetteer_fixture(3, "Essex", 51.75, 0.55),
+            gazetteer_fixture(4, "London", 51.51, -0.13),
+        ];
+        geonames_data[1].feature_code = "PPLA2".to_string();
+        geonames_data[2].feature_code = "ADM2".to_string();
+        geonames_data[3].feature_code = "PPLC".to_string();
+        let location = GeoLocation::from((51.79, 0.63));
+
+        let names = |code_prefix| -> Vec<&str> {
+            places_within_radius_by_feature(location.clone(), 20.0, &geonames_data, code_prefix)
+                .iter()
+                .map(|geoname| geoname.name.as_str())
+                .collect()
+        };
+        assert_eq!(names("PPL"), ["Witham", "Chelmsford"]);
+        assert_eq!(names("PPLA"), ["Chelmsford"]);
+        assert!(names("PPLC").is_empty());
+    }
+
+    #[test_log::test]
+    fn test_get_place_by_alternate_name() {
+        let geonames_data = vec![gazetteer_fixture(2867714, "Munich", 48.14, 11.58)];
+        let alternate_names =
+            load_alternate_names_data("1557374\t2867714\tde\tMünchen\t1\t\t\t\t\t\n");
+
+        let place =
+            get_place_by_alternate_name("München", Some("de"), &alternate_names, &geonames_data);
+        assert_eq!(place.unwrap().name, "Munich");
+        assert!(
+            get_place_by_alternate_name("München", None, &alternate_names, &geonames_data)
+                .is_some()
+        );
+        assert!(get_place_by_alternate_name(
+            "München",
+            Some("fr"),
+            &alternate_names,
+            &geonames_data
+        )
+        .is_none());
+    }
+
+    #[test_log::test]
+    fn test_gazetteer_display() {
+        let place = gazetteer_fixture(2634633, "Witham", 51.80, 0.64);
+        assert_eq!(place.to_string(), "Witham (PPL, GB)");
+    }
+
+    #[test_log::test]
+    fn test_get_unique_places_within_radius() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let geonames_data = vec![
+            gazetteer_fixture(1, "Witham", 51.80, 0.64),
+            gazetteer_fixture(2, "Maldon", 51.73, 0.68),
+            gazetteer_fixture(3, "Witham", 51.80, 0.65),
+            gazetteer_fixture(4, "London", 51.51, -0.13),
+        ];
+
+        let places = get_unique_places_within_radius(location, 10.0, &geonames_data);
+        assert_eq!(places, ["Maldon", "Witham"]);
+    }
+
+    #[test_log::test]
+    fn test_population_within_radius() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let mut geonames_data = vec![
+            gazetteer_fixture(1, "Witham", 51.80, 0.64),
+            gazetteer_fixture(2, "Maldon", 51.73, 0.68),
+            gazetteer_fixture(1, "Witham", 51.80, 0.64),
+            gazetteer_fixture(3, "Essex", 51.75, 0.60),
+            gazetteer_fixture(4, "London", 51.51, -0.13),
+        ];
+        for (geoname, population) in geonames_data
+            .iter_mut()
+            .zip([25_000, 15_000, 25_000, 1_500_000, 9_000_000])
+        {
+            geoname.population = population;
+        }
+        geonames_data[3].feature_class = "A".to_string();
+        geonames_data[3].feature_code = "ADM2".to_string();
+
+        assert_eq!(
+            population_within_radius(location, 10.0, &geonames_data),
+            40_000
+        );
+    }
+
     #[test_log::test]
     fn test_get_postal_data() {
         let geonames_data = GEONAMES_POSTAL_DATA.clone();
@@ -182,8 +1126,24 @@ mod tests {
         assert!(postcode.is_some());
     }
 
+    #[test_log::test]
+    fn test_postcode_exists() {
+        let witham = postal_fixture("CM8", 51.80, 0.64);
+        let mut paris = postal_fixture("75001", 48.86, 2.34);
+        paris.country_code = "FR".to_string();
+        let geonames_data = vec![witham, paris];
+
+        assert!(postcode_exists("CM8", &geonames_data));
+        assert!(postcode_exists(" cm8 ", &geonames_data));
+        assert!(!postcode_exists("CM9", &geonames_data));
+
+        assert!(postcode_exists_in("cm8", "gb", &geonames_data));
+        assert!(postcode_exists_in("75001", "FR", &geonames_data));
+        assert!(!postcode_exists_in("75001", "GB", &geonames_data));
+    }
+
     #[test_log::test]
     fn test_invalidate_cache() {
-        invalidate_cache();
+        invalidate_cache().unwrap();
     }
 }
@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// How accurately the location of a postal code is known.
+///
+/// Accuracies are ordered from worst to best: `NoLocation`, then `NoAccuracyData`, then
+/// every numeric code in ascending order, as GeoNames uses higher codes for more accurate
+/// locations. An `Unknown` code ranks by its number, so `Unknown(5)` is between
+/// `GeonameId` (4) and `Centroid` (6), and just below a known code with the same number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Accuracy {
+    NoLocation,
+    NoAccuracyData,
+    // a numeric code this crate does not know the meaning of
+    Unknown(u8),
+    Estimated,
+    NeighbouringCentroid,
+    SamePostalCodeOtherName,
+    GeonameId,
+    Centroid,
+}
+
+impl Accuracy {
+    /// Get the GeoNames accuracy code for this accuracy.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the numeric code, or `None` for `NoAccuracyData`.
+    pub fn code(&self) -> Option<u8> {
+        match self {
+            Accuracy::NoLocation => Some(0),
+            Accuracy::Estimated => Some(1),
+            Accuracy::NeighbouringCentroid => Some(2),
+            Accuracy::SamePostalCodeOtherName => Some(3),
+            Accuracy::GeonameId => Some(4),
+            Accuracy::Centroid => Some(6),
+            Accuracy::Unknown(code) => Some(*code),
+            Accuracy::NoAccuracyData => None,
+        }
+    }
+
+    // the position of this accuracy in the documented order, best last
+    fn rank(&self) -> (u8, u8, bool) {
+        match self {
+            Accuracy::NoLocation => (0, 0, false),
+            Accuracy::NoAccuracyData => (1, 0, false),
+            Accuracy::Unknown(code) => (2, *code, false),
+            known => (2, known.code().unwrap_or_default(), true),
+        }
+    }
+}
+
+impl Ord for Accuracy {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Accuracy {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Parses a GeoNames accuracy code, so `"06"` is `Centroid`. Numeric codes without a known
+/// meaning are kept as `Unknown`, and anything else, such as a blank field, is `NoAccuracyData`.
+impl FromStr for Accuracy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Ok(code) = s.trim().parse::<u8>() else {
+            return Ok(Accuracy::NoAccuracyData);
+        };
+
+        Ok(match code {
+            // higher is better
+            0 => Accuracy::NoLocation,
+            1 => Accuracy::Estimated,
+            2 => Accuracy::NeighbouringCentroid,
+            3 => Accuracy::SamePostalCodeOtherName,
+            4 => Accuracy::GeonameId,
+            6 => Accuracy::Centroid,
+            code => Accuracy::Unknown(code),
+        })
+    }
+}
+
+impl Display for Accuracy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Accuracy::Estimated => write!(
+                f,
+                "Estimated as average from numerically neighbouring postal codes"
+            ),
+            Accuracy::NeighbouringCentroid => write!(
+                f,
+                "Centroid of gravity of numerically neighbouring postal codes"
+            ),
+            Accuracy::SamePostalCodeOtherName => write!(f, "Same postal code, other name"),
+            Accuracy::GeonameId => write!(f, "Place name from geoname id"),
+            Accuracy::Centroid => write!(f, "Postal code area centroid"),
+            Accuracy::NoAccuracyData => write!(f, "No accuracy data"),
+            Accuracy::NoLocation => write!(f, "No location"),
+            Accuracy::Unknown(code) => write!(f, "Unknown accuracy code {}", code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("2".parse(), Ok(Accuracy::NeighbouringCentroid));
+        assert_eq!("5".parse(), Ok(Accuracy::Unknown(5)));
+        assert_eq!("".parse(), Ok(Accuracy::NoAccuracyData));
+        assert_eq!("x".parse(), Ok(Accuracy::NoAccuracyData));
+
+        for code in 0..=7 {
+            let accuracy: Accuracy = code.to_string().parse().unwrap();
+            assert_eq!(accuracy.code(), Some(code));
+        }
+        assert!(Accuracy::Centroid > Accuracy::NeighbouringCentroid);
+        assert_eq!("06".parse(), Ok(Accuracy::Centroid));
+        assert_eq!(" 4".parse(), Ok(Accuracy::GeonameId));
+    }
+
+    #[test]
+    fn test_ord() {
+        let unknown: Accuracy = "5".parse().unwrap();
+        assert!(unknown > Accuracy::Estimated);
+        assert!(unknown > Accuracy::GeonameId);
+        assert!(unknown < Accuracy::Centroid);
+        assert!(Accuracy::Unknown(6) < Accuracy::Centroid);
+        assert!(Accuracy::Unknown(7) > Accuracy::Centroid);
+
+        let mut accuracies = vec![
+            Accuracy::Centroid,
+            Accuracy::Unknown(5),
+            Accuracy::NoAccuracyData,
+            Accuracy::Estimated,
+            Accuracy::NoLocation,
+        ];
+        accuracies.sort();
+        assert_eq!(
+            accuracies,
+            [
+                Accuracy::NoLocation,
+                Accuracy::NoAccuracyData,
+                Accuracy::Estimated,
+                Accuracy::Unknown(5),
+                Accuracy::Centroid,
+            ]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+/// A point of the 8-point compass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompassDirection {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl CompassDirection {
+    /// Get the compass point closest to a bearing.
+    ///
+    /// Each point covers the 45° sector centred on it, so `North` covers bearings from
+    /// 337.5° up to 22.5°.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearing` - A `f64` representing the bearing in degrees clockwise from north.
+    ///
+    /// # Returns
+    ///
+    /// The `CompassDirection` closest to the bearing.
+    pub fn from_bearing(bearing: f64) -> Self {
+        const DIRECTIONS: [CompassDirection; 8] = [
+            CompassDirection::North,
+            CompassDirection::NorthEast,
+            CompassDirection::East,
+            CompassDirection::SouthEast,
+            CompassDirection::South,
+            CompassDirection::SouthWest,
+            CompassDirection::West,
+            CompassDirection::NorthWest,
+        ];
+
+        let sector = (bearing.rem_euclid(360.0) / 45.0).round() as usize;
+        DIRECTIONS[sector % 8]
+    }
+
+    /// Get the abbreviation of the compass point, e.g. `NE`.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            CompassDirection::North => "N",
+            CompassDirection::NorthEast => "NE",
+            CompassDirection::East => "E",
+            CompassDirection::SouthEast => "SE",
+            CompassDirection::South => "S",
+            CompassDirection::SouthWest => "SW",
+            CompassDirection::West => "W",
+            CompassDirection::NorthWest => "NW",
+        }
+    }
+}
+
+/// Formats the compass point as its abbreviation, e.g. `NE`.
+impl Display for CompassDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bearing() {
+        assert_eq!(CompassDirection::from_bearing(0.0), CompassDirection::North);
+        assert_eq!(
+            CompassDirection::from_bearing(350.0),
+            CompassDirection::North
+        );
+        assert_eq!(
+            CompassDirection::from_bearing(22.4),
+            CompassDirection::North
+        );
+        assert_eq!(
+            CompassDirection::from_bearing(22.6),
+            CompassDirection::NorthEast
+        );
+        assert_eq!(
+            CompassDirection::from_bearing(180.0),
+            CompassDirection::South
+        );
+        assert_eq!(
+            CompassDirection::from_bearing(-90.0),
+            CompassDirection::West
+        );
+        assert_eq!(CompassDirection::NorthWest.to_string(), "NW");
+    }
+}
@@ -1,7 +1,10 @@
 use std::fmt::Display;
 use std::str::FromStr;
 
-#[derive(PartialEq, Debug)]
+/// A country, or set of countries, that GeoNames publishes data for.
+///
+/// Use `Country::other` with an ISO 3166-1 alpha-2 code for a country that is not listed.
+#[derive(PartialEq, Debug, Clone)]
 pub enum Country {
     All,
     AlandIslands,
@@ -108,118 +111,435 @@ pub enum Country {
     VaticanCity,
     VirginIslandsUS,
     WallisAndFutuna,
+    // a country that is not listed above, by its uppercase ISO 3166-1 alpha-2 code
+    Other(CountryCode),
 }
 
-impl Display for Country {
+/// A validated, uppercase ISO 3166-1 alpha-2 code for a `Country::Other`.
+///
+/// The code is always two ASCII letters, so it is safe to use in cache paths and URLs.
+/// Create one with `Country::other` or `Country::from_str`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct CountryCode(String);
+
+impl CountryCode {
+    /// Get the code as a `&str`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for CountryCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Country {
+    /// Create a `Country` from an ISO 3166-1 alpha-2 code.
+    ///
+    /// The code is uppercased, and codes of listed countries return their named variant,
+    /// so `Country::other("fr")` is `Country::France`.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A `&str` representing the two letter country code.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Country`, or an error if the code is not two ASCII letters.
+    pub fn other(code: &str) -> Result<Country, String> {
+        if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("Invalid country code: {}", code));
+        }
+
+        Country::from_str(code)
+    }
+
+    /// Get every country GeoNames publishes postal data for.
+    ///
+    /// Each dataset is listed once, so `Country::All`, `Country::Other` and the
+    /// `GreatBritain` aliases of the `UnitedKingdom` variants are not included.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of `Country` enums, including the `*Full` variants.
+    pub fn all() -> Vec<Country> {
+        vec![
+            Country::AlandIslands,
+            Country::Algeria,
+            Country::AmericanSamoa,
+            Country::Andorra,
+            Country::Argentina,
+            Country::Australia,
+            Country::Austria,
+            Country::Azerbaijan,
+            Country::Bangladesh,
+            Country::Belarus,
+            Country::Belgium,
+            Country::Bermuda,
+            Country::Brazil,
+            Country::Bulgaria,
+            Country::Canada,
+            Country::CanadaFull,
+            Country::Chile,
+            Country::China,
+            Country::Colombia,
+            Country::CostaRica,
+            Country::Croatia,
+            Country::Cyprus,
+            Country::Czechia,
+            Country::Denmark,
+            Country::DominicanRepublic,
+            Country::Ecuador,
+            Country::Estonia,
+            Country::FaroeIslands,
+            Country::Finland,
+            Country::France,
+            Country::FrenchGuiana,
+            Country::Germany,
+            Country::Greenland,
+            Country::Guadeloupe,
+            Country::Guam,
+            Country::Guatemala,
+            Country::Guernsey,
+            Country::Haiti,
+            Country::Hungary,
+            Country::Iceland,
+            Country::India,
+            Country::Ireland,
+            Country::IsleOfMan,
+            Country::Italy,
+            Country::Japan,
+            Country::Jersey,
+            Country::Latvia,
+            Country::Liechtenstein,
+            Country::Lithuania,
+            Country::Luxembourg,
+            Country::Malawi,
+            Country::Malaysia,
+            Country::Malta,
+            Country::MarshallIslands,
+            Country::Martinique,
+            Country::Mayotte,
+            Country::Mexico,
+            Country::Micronesia,
+            Country::Moldova,
+            Country::Monaco,
+            Country::Morocco,
+            Country::Netherlands,
+            Country::NetherlandsFull,
+            Country::NewCaledonia,
+            Country::NewZealand,
+            Country::NorthernMarianaIslands,
+            Country::NorthMacedonia,
+            Country::Norway,
+            Country::Pakistan,
+            Country::Palau,
+            Country::Panama,
+            Country::Peru,
+            Country::Philippines,
+            Country::Poland,
+            Country::Portugal,
+            Country::PuertoRico,
+            Country::Reunion,
+            Country::Romania,
+            Country::Russia,
+            Country::SaintPierreAndMiquelon,
+            Country::SanMarino,
+            Country::Serbia,
+            Country::Singapore,
+            Country::Slovakia,
+            Country::Slovenia,
+            Country::SouthAfrica,
+            Country::SouthKorea,
+            Country::Spain,
+            Country::SriLanka,
+            Country::SvalbardAndJanMayen,
+            Country::Sweden,
+            Country::Switzerland,
+            Country::Thailand,
+            Country::Turkey,
+            Country::Ukraine,
+            Country::UnitedKingdom,
+            Country::UnitedKingdomFull,
+            Country::UnitedStates,
+            Country::Uruguay,
+            Country::VaticanCity,
+            Country::VirginIslandsUS,
+            Country::WallisAndFutuna,
+        ]
+    }
+
+    /// Get the human readable name of the country.
+    ///
+    /// `Country::Other` has no known name, so its code is returned instead.
+    ///
+    /// # Returns
+    ///
+    /// A `&str` such as `"United Kingdom"`.
+    pub fn display_name(&self) -> &str {
+        match self {
+            Country::All => "All Countries",
+            Country::AlandIslands => "Åland Islands",
+            Country::Algeria => "Algeria",
+            Country::AmericanSamoa => "American Samoa",
+            Country::Andorra => "Andorra",
+            Country::Argentina => "Argentina",
+            Country::Australia => "Australia",
+            Country::Austria => "Austria",
+            Country::Azerbaijan => "Azerbaijan",
+            Country::Bangladesh => "Bangladesh",
+            Country::Belarus => "Belarus",
+            Country::Belgium => "Belgium",
+            Country::Bermuda => "Bermuda",
+            Country::Brazil => "Brazil",
+            Country::Bulgaria => "Bulgaria",
+            Country::Canada => "Canada",
+            Country::CanadaFull => "Canada (full postal codes)",
+            Country::Chile => "Chile",
+            Country::China => "China",
+            Country::Colombia => "Colombia",
+            Country::CostaRica => "Costa Rica",
+            Country::Croatia => "Croatia",
+            Country::Cyprus => "Cyprus",
+            Country::Czechia => "Czechia",
+            Country::Denmark => "Denmark",
+            Country::DominicanRepublic => "Dominican Republic",
+            Country::Ecuador => "Ecuador",
+            Country::Estonia => "Estonia",
+            Country::FaroeIslands => "Faroe Islands",
+            Country::Finland => "Finland",
+            Country::France => "France",
+            Country::FrenchGuiana => "French Guiana",
+            Country::Germany => "Germany",
+            Country::GreatBritain => "Great Britain",
+            Country::GreatBritainFull => "Great Britain (full postal codes)",
+            Country::Greenland => "Greenland",
+            Country::Guadeloupe => "Guadeloupe",
+            Country::Guam => "Guam",
+            Country::Guatemala => "Guatemala",
+            Country::Guernsey => "Guernsey",
+            Country::Haiti => "Haiti",
+            Country::Hungary => "Hungary",
+            Country::Iceland => "Iceland",
+            Country::India => "India",
+            Country::Ireland => "Ireland",
+            Country::IsleOfMan => "Isle of Man",
+            Country::Italy => "Italy",
+            Country::Japan => "Japan",
+            Country::Jersey => "Jersey",
+            Country::Latvia => "Latvia",
+            Country::Liechtenstein => "Liechtenstein",
+            Country::Lithuania => "Lithuania",
+            Country::Luxembourg => "Luxembourg",
+            Country::Malawi => "Malawi",
+            Country::Malaysia => "Malaysia",
+            Country::Malta => "Malta",
+            Country::MarshallIslands => "Marshall Islands",
+            Country::Martinique => "Martinique",
+            Country::Mayotte => "Mayotte",
+            Country::Mexico => "Mexico",
+            Country::Micronesia => "Micronesia",
+            Country::Moldova => "Moldova",
+            Country::Monaco => "Monaco",
+            Country::Morocco => "Morocco",
+            Country::Netherlands => "Netherlands",
+            Country::NetherlandsFull => "Netherlands (full postal codes)",
+            Country::NewCaledonia => "New Caledonia",
+            Country::NewZealand => "New Zealand",
+            Country::NorthernMarianaIslands => "Northern Mariana Islands",
+            Country::NorthMacedonia => "North Macedonia",
+            Country::Norway => "Norway",
+            Country::Pakistan => "Pakistan",
+            Country::Palau => "Palau",
+            Country::Panama => "Panama",
+            Country::Peru => "Peru",
+            Country::Philippines => "Philippines",
+            Country::Poland => "Poland",
+            Country::Portugal => "Portugal",
+            Country::PuertoRico => "Puerto Rico",
+            Country::Reunion => "Réunion",
+            Country::Romania => "Romania",
+            Country::Russia => "Russia",
+            Country::SaintPierreAndMiquelon => "Saint Pierre and Miquelon",
+            Country::SanMarino => "San Marino",
+            Country::Serbia => "Serbia",
+            Country::Singapore => "Singapore",
+            Country::Slovakia => "Slovakia",
+            Country::Slovenia => "Slovenia",
+            Country::SouthAfrica => "South Africa",
+            Country::SouthKorea => "South Korea",
+            Country::Spain => "Spain",
+            Country::SriLanka => "Sri Lanka",
+            Country::SvalbardAndJanMayen => "Svalbard and Jan Mayen",
+            Country::Sweden => "Sweden",
+            Country::Switzerland => "Switzerland",
+            Country::Thailand => "Thailand",
+            Country::Turkey => "Türkiye",
+            Country::Ukraine => "Ukraine",
+            Country::UnitedKingdom => "United Kingdom",
+            Country::UnitedKingdomFull => "United Kingdom (full postal codes)",
+            Country::UnitedStates => "United States",
+            Country::Uruguay => "Uruguay",
+            Country::VaticanCity => "Vatican City",
+            Country::VirginIslandsUS => "U.S. Virgin Islands",
+            Country::WallisAndFutuna => "Wallis and Futuna",
+            Country::Other(code) => code.as_str(),
+        }
+    }
+
+    /// Get the ISO 3166-1 alpha-2 code of the country.
+    ///
+    /// The `*Full` variants share the code of their country.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the two letter code, or `None` for `Country::All`.
+    pub fn iso_alpha2(&self) -> Option<&str> {
         match self {
-            Country::UnitedKingdomFull => write!(f, "GB_full"),
-            Country::GreatBritainFull => write!(f, "GB_full"),
-            Country::CanadaFull => write!(f, "CA_full"),
-            Country::NetherlandsFull => write!(f, "NL_full"),
-            Country::Andorra => write!(f, "AD"),
-            Country::Argentina => write!(f, "AR"),
-            Country::AmericanSamoa => write!(f, "AS"),
-            Country::Austria => write!(f, "AT"),
-            Country::Australia => write!(f, "AU"),
-            Country::AlandIslands => write!(f, "AX"),
-            Country::Azerbaijan => write!(f, "AZ"),
-            Country::Bangladesh => write!(f, "BD"),
-            Country::Belgium => write!(f, "BE"),
-            Country::Bulgaria => write!(f, "BG"),
-            Country::Bermuda => write!(f, "BM"),
-            Country::Brazil => write!(f, "BR"),
-            Country::Belarus => write!(f, "BY"),
-            Country::Canada => write!(f, "CA"),
-            Country::Switzerland => write!(f, "CH"),
-            Country::Chile => write!(f, "CL"),
-            Country::China => write!(f, "CN"),
-            Country::Colombia => write!(f, "CO"),
-            Country::CostaRica => write!(f, "CR"),
-            Country::Cyprus => write!(f, "CY"),
-            Country::Czechia => write!(f, "CZ"),
-            Country::Germany => write!(f, "DE"),
-            Country::Denmark => write!(f, "DK"),
-            Country::DominicanRepublic => write!(f, "DO"),
-            Country::Algeria => write!(f, "DZ"),
-            Country::Ecuador => write!(f, "EC"),
-            Country::Estonia => write!(f, "EE"),
-            Country::Spain => write!(f, "ES"),
-            Country::Finland => write!(f, "FI"),
-            Country::Micronesia => write!(f, "FM"),
-            Country::FaroeIslands => write!(f, "FO"),
-            Country::France => write!(f, "FR"),
-            Country::UnitedKingdom => write!(f, "GB"),
-            Country::GreatBritain => write!(f, "GB"),
-            Country::FrenchGuiana => write!(f, "GF"),
-            Country::Guernsey => write!(f, "GG"),
-            Country::Greenland => write!(f, "GL"),
-            Country::Guadeloupe => write!(f, "GP"),
-            Country::Guatemala => write!(f, "GT"),
-            Country::Guam => write!(f, "GU"),
-            Country::Croatia => write!(f, "HR"),
-            Country::Haiti => write!(f, "HT"),
-            Country::Hungary => write!(f, "HU"),
-            Country::Ireland => write!(f, "IE"),
-            Country::IsleOfMan => write!(f, "IM"),
-            Country::India => write!(f, "IN"),
-            Country::Iceland => write!(f, "IS"),
-            Country::Italy => write!(f, "IT"),
-            Country::Jersey => write!(f, "JE"),
-            Country::Japan => write!(f, "JP"),
-            Country::SouthKorea => write!(f, "KR"),
-            Country::Liechtenstein => write!(f, "LI"),
-            Country::SriLanka => write!(f, "LK"),
-            Country::Lithuania => write!(f, "LT"),
-            Country::Luxembourg => write!(f, "LU"),
-            Country::Latvia => write!(f, "LV"),
-            Country::Morocco => write!(f, "MA"),
-            Country::Monaco => write!(f, "MC"),
-            Country::Moldova => write!(f, "MD"),
-            Country::MarshallIslands => write!(f, "MH"),
-            Country::NorthMacedonia => write!(f, "MK"),
-            Country::NorthernMarianaIslands => write!(f, "MP"),
-            Country::Martinique => write!(f, "MQ"),
-            Country::Malta => write!(f, "MT"),
-            Country::Malawi => write!(f, "MW"),
-            Country::Mexico => write!(f, "MX"),
-            Country::Malaysia => write!(f, "MY"),
-            Country::NewCaledonia => write!(f, "NC"),
-            Country::Netherlands => write!(f, "NL"),
-            Country::Norway => write!(f, "NO"),
-            Country::NewZealand => write!(f, "NZ"),
-            Country::Panama => write!(f, "PA"),
-            Country::Peru => write!(f, "PE"),
-            Country::Philippines => write!(f, "PH"),
-            Country::Pakistan => write!(f, "PK"),
-            Country::Poland => write!(f, "PL"),
-            Country::SaintPierreAndMiquelon => write!(f, "PM"),
-            Country::PuertoRico => write!(f, "PR"),
-            Country::Portugal => write!(f, "PT"),
-            Country::Palau => write!(f, "PW"),
-            Country::Reunion => write!(f, "RE"),
-            Country::Romania => write!(f, "RO"),
-            Country::Serbia => write!(f, "RS"),
-            Country::Russia => write!(f, "RU"),
-            Country::Sweden => write!(f, "SE"),
-            Country::Singapore => write!(f, "SG"),
-            Country::Slovenia => write!(f, "SI"),
-            Country::SvalbardAndJanMayen => write!(f, "SJ"),
-            Country::Slovakia => write!(f, "SK"),
-            Country::SanMarino => write!(f, "SM"),
-            Country::Thailand => write!(f, "TH"),
-            Country::Turkey => write!(f, "TR"),
-            Country::Ukraine => write!(f, "UA"),
-            Country::UnitedStates => write!(f, "US"),
-            Country::Uruguay => write!(f, "UY"),
-            Country::VaticanCity => write!(f, "VA"),
-            Country::VirginIslandsUS => write!(f, "VI"),
-            Country::WallisAndFutuna => write!(f, "WF"),
-            Country::Mayotte => write!(f, "YT"),
-            Country::SouthAfrica => write!(f, "ZA"),
-            Country::All => write!(f, "allCountries"),
+            Country::All => None,
+            Country::UnitedKingdomFull | Country::GreatBritainFull => Some("GB"),
+            Country::CanadaFull => Some("CA"),
+            Country::NetherlandsFull => Some("NL"),
+            country => Some(country.file_stem()),
         }
     }
+
+    /// Check whether GeoNames publishes gazetteer data for the country.
+    ///
+    /// The `*Full` variants only exist for postal data.
+    ///
+    /// # Returns
+    ///
+    /// `true` if `get_gazetteer_data` can be used with the country.
+    pub fn supports_gazetteer(&self) -> bool {
+        !matches!(
+            self,
+            Country::GreatBritainFull
+                | Country::UnitedKingdomFull
+                | Country::NetherlandsFull
+                | Country::CanadaFull
+        )
+    }
+
+    // the name GeoNames uses for the country's files
+    fn file_stem(&self) -> &str {
+        match self {
+            Country::UnitedKingdomFull => "GB_full",
+            Country::GreatBritainFull => "GB_full",
+            Country::CanadaFull => "CA_full",
+            Country::NetherlandsFull => "NL_full",
+            Country::Andorra => "AD",
+            Country::Argentina => "AR",
+            Country::AmericanSamoa => "AS",
+            Country::Austria => "AT",
+            Country::Australia => "AU",
+            Country::AlandIslands => "AX",
+            Country::Azerbaijan => "AZ",
+            Country::Bangladesh => "BD",
+            Country::Belgium => "BE",
+            Country::Bulgaria => "BG",
+            Country::Bermuda => "BM",
+            Country::Brazil => "BR",
+            Country::Belarus => "BY",
+            Country::Canada => "CA",
+            Country::Switzerland => "CH",
+            Country::Chile => "CL",
+            Country::China => "CN",
+            Country::Colombia => "CO",
+            Country::CostaRica => "CR",
+            Country::Cyprus => "CY",
+            Country::Czechia => "CZ",
+            Country::Germany => "DE",
+            Country::Denmark => "DK",
+            Country::DominicanRepublic => "DO",
+            Country::Algeria => "DZ",
+            Country::Ecuador => "EC",
+            Country::Estonia => "EE",
+            Country::Spain => "ES",
+            Country::Finland => "FI",
+            Country::Micronesia => "FM",
+            Country::FaroeIslands => "FO",
+            Country::France => "FR",
+            Country::UnitedKingdom => "GB",
+            Country::GreatBritain => "GB",
+            Country::FrenchGuiana => "GF",
+            Country::Guernsey => "GG",
+            Country::Greenland => "GL",
+            Country::Guadeloupe => "GP",
+            Country::Guatemala => "GT",
+            Country::Guam => "GU",
+            Country::Croatia => "HR",
+            Country::Haiti => "HT",
+            Country::Hungary => "HU",
+            Country::Ireland => "IE",
+            Country::IsleOfMan => "IM",
+            Country::India => "IN",
+            Country::Iceland => "IS",
+            Country::Italy => "IT",
+            Country::Jersey => "JE",
+            Country::Japan => "JP",
+            Country::SouthKorea => "KR",
+            Country::Liechtenstein => "LI",
+            Country::SriLanka => "LK",
+            Country::Lithuania => "LT",
+            Country::Luxembourg => "LU",
+            Country::Latvia => "LV",
+            Country::Morocco => "MA",
+            Country::Monaco => "MC",
+            Country::Moldova => "MD",
+            Country::MarshallIslands => "MH",
+            Country::NorthMacedonia => "MK",
+            Country::NorthernMarianaIslands => "MP",
+            Country::Martinique => "MQ",
+            Country::Malta => "MT",
+            Country::Malawi => "MW",
+            Country::Mexico => "MX",
+            Country::Malaysia => "MY",
+            Country::NewCaledonia => "NC",
+            Country::Netherlands => "NL",
+            Country::Norway => "NO",
+            Country::NewZealand => "NZ",
+            Country::Panama => "PA",
+            Country::Peru => "PE",
+            Country::Philippines => "PH",
+            Country::Pakistan => "PK",
+            Country::Poland => "PL",
+            Country::SaintPierreAndMiquelon => "PM",
+            Country::PuertoRico => "PR",
+            Country::Portugal => "PT",
+            Country::Palau => "PW",
+            Country::Reunion => "RE",
+            Country::Romania => "RO",
+            Country::Serbia => "RS",
+            Country::Russia => "RU",
+            Country::Sweden => "SE",
+            Country::Singapore => "SG",
+            Country::Slovenia => "SI",
+            Country::SvalbardAndJanMayen => "SJ",
+            Country::Slovakia => "SK",
+            Country::SanMarino => "SM",
+            Country::Thailand => "TH",
+            Country::Turkey => "TR",
+            Country::Ukraine => "UA",
+            Country::UnitedStates => "US",
+            Country::Uruguay => "UY",
+            Country::VaticanCity => "VA",
+            Country::VirginIslandsUS => "VI",
+            Country::WallisAndFutuna => "WF",
+            Country::Mayotte => "YT",
+            Country::SouthAfrica => "ZA",
+            Country::All => "allCountries",
+            Country::Other(code) => code.as_str(),
+        }
+    }
+}
+
+impl Display for Country {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file_stem())
+    }
 }
 
 impl FromStr for Country {
@@ -228,6 +548,10 @@ impl FromStr for Country {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.to_uppercase();
         match s.as_str() {
+            "ALLCOUNTRIES" => Ok(Country::All),
+            "GB_FULL" => Ok(Country::UnitedKingdomFull),
+            "CA_FULL" => Ok(Country::CanadaFull),
+            "NL_FULL" => Ok(Country::NetherlandsFull),
             "AD" => Ok(Country::Andorra),
             "AR" => Ok(Country::Argentina),
             "AS" => Ok(Country::AmericanSamoa),
@@ -327,6 +651,9 @@ impl FromStr for Country {
             "WF" => Ok(Country::WallisAndFutuna),
             "YT" => Ok(Country::Mayotte),
             "ZA" => Ok(Country::SouthAfrica),
+            code if code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) => {
+                Ok(Country::Other(CountryCode(code.to_string())))
+            }
             _ => Err(format!("Unknown country code: {}", s)),
         }
     }
@@ -342,6 +669,56 @@ mod tests {
         assert_eq!(country, Ok(Country::UnitedStates));
     }
 
+    #[test]
+    fn test_from_str_round_trip() {
+        for country in [
+            Country::All,
+            Country::UnitedKingdomFull,
+            Country::CanadaFull,
+        ] {
+            assert_eq!(Country::from_str(&country.to_string()), Ok(country));
+        }
+    }
+
+    #[test]
+    fn test_all_round_trip() {
+        let countries = Country::all();
+        assert!(countries.contains(&Country::France));
+        assert!(!countries.contains(&Country::All));
+
+        for country in countries {
+            assert!(!matches!(country, Country::Other(_)));
+            assert_eq!(Country::from_str(&country.to_string()), Ok(country));
+        }
+    }
+
+    #[test]
+    fn test_other() {
+        let kosovo = Country::other("xk").unwrap();
+        assert_eq!(Country::from_str("xk"), Ok(kosovo.clone()));
+        assert!(matches!(&kosovo, Country::Other(code) if code.as_str() == "XK"));
+        assert_eq!(kosovo.to_string(), "XK");
+        assert_eq!(kosovo.iso_alpha2(), Some("XK"));
+        assert!(Country::from_str("XKX").is_err());
+        assert!(Country::from_str("1A").is_err());
+
+        assert_eq!(Country::other("fr"), Ok(Country::France));
+        assert!(Country::other("../../etc/x").is_err());
+        assert!(Country::other("allCountries").is_err());
+        assert!(Country::other("X").is_err());
+        assert!(Country::other("ÄÖ").is_err());
+    }
+
+    #[test]
+    fn test_metadata() {
+        assert_eq!(Country::UnitedKingdom.display_name(), "United Kingdom");
+        assert_eq!(Country::UnitedKingdom.iso_alpha2(), Some("GB"));
+        assert_eq!(Country::CanadaFull.iso_alpha2(), Some("CA"));
+        assert_eq!(Country::All.iso_alpha2(), None);
+        assert!(Country::UnitedKingdom.supports_gazetteer());
+        assert!(!Country::UnitedKingdomFull.supports_gazetteer());
+    }
+
     #[test]
     fn test_to_string() {
         let country = Country::UnitedStates;
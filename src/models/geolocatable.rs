@@ -0,0 +1,40 @@
+use crate::{Gazetteer, GeoLocation, PostalData, PostalSummary};
+
+/// A record that may have a geolocation.
+///
+/// Implement this for your own types to use them with the generic query functions
+/// such as `nearest` and `records_within_radius`.
+pub trait Geolocatable {
+    /// Get the geolocation of the record, if it has one.
+    fn geolocation(&self) -> Option<&GeoLocation>;
+}
+
+impl Geolocatable for GeoLocation {
+    fn geolocation(&self) -> Option<&GeoLocation> {
+        Some(self)
+    }
+}
+
+impl Geolocatable for PostalData {
+    fn geolocation(&self) -> Option<&GeoLocation> {
+        self.geolocation.as_ref()
+    }
+}
+
+impl Geolocatable for PostalSummary {
+    fn geolocation(&self) -> Option<&GeoLocation> {
+        Some(&self.location)
+    }
+}
+
+impl Geolocatable for Gazetteer {
+    fn geolocation(&self) -> Option<&GeoLocation> {
+        self.geolocation.as_ref()
+    }
+}
+
+impl<T: Geolocatable + ?Sized> Geolocatable for &T {
+    fn geolocation(&self) -> Option<&GeoLocation> {
+        (**self).geolocation()
+    }
+}
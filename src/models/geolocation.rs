@@ -1,6 +1,10 @@
-use crate::haversine::calculate_distance;
+use std::hash::{Hash, Hasher};
+
+use crate::haversine::{calculate_distance, initial_bearing, within_radius, BoundingBox};
+use crate::CompassDirection;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GeoLocation {
     pub latitude: f64,
     pub longitude: f64,
@@ -10,4 +14,331 @@ impl GeoLocation {
     pub fn distance(&self, other: &GeoLocation) -> f64 {
         calculate_distance(self, other)
     }
+
+    /// Check if another location is within a certain distance of this one.
+    ///
+    /// The boundary is inclusive.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - A `GeoLocation` struct representing the other location.
+    /// * `radius_km` - A `f64` representing the radius in kilometers.
+    ///
+    /// # Returns
+    ///
+    /// A `bool` indicating whether `other` is within `radius_km` of this location.
+    pub fn is_within(&self, other: &GeoLocation, radius_km: f64) -> bool {
+        within_radius(self, other, radius_km)
+    }
+
+    /// Get the compass direction of another location from this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - A `GeoLocation` struct representing the other location.
+    ///
+    /// # Returns
+    ///
+    /// The `CompassDirection` of the initial bearing from this location to `other`.
+    pub fn cardinal_to(&self, other: &GeoLocation) -> CompassDirection {
+        CompassDirection::from_bearing(initial_bearing(self, other))
+    }
+
+    /// Get the bounding box around this location.
+    ///
+    /// # Arguments
+    ///
+    /// * `radius_km` - A `f64` representing the distance from the centre to each edge in kilometers.
+    ///
+    /// # Returns
+    ///
+    /// A `BoundingBox` struct centred on this location.
+    pub fn bounding_box(&self, radius_km: f64) -> BoundingBox {
+        BoundingBox::new(self, radius_km)
+    }
+
+    /// Convert the location into a `geo_types::Point`.
+    ///
+    /// `geo` uses `(x, y)` ordering, so `x` is the longitude and `y` is the latitude.
+    ///
+    /// # Returns
+    ///
+    /// A `Point<f64>` with `x = longitude` and `y = latitude`.
+    #[cfg(feature = "geo-types")]
+    pub fn to_geo_point(&self) -> geo_types::Point<f64> {
+        geo_types::Point::new(self.longitude, self.latitude)
+    }
+
+    /// Create a `GeoLocation`, repairing out of range coordinates instead of rejecting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - A `f64` representing the latitude in decimal degrees.
+    /// * `longitude` - A `f64` representing the longitude in decimal degrees.
+    ///
+    /// # Returns
+    ///
+    /// A `GeoLocation` struct, normalized as described in `GeoLocation::normalized`.
+    pub fn new_normalized(latitude: f64, longitude: f64) -> GeoLocation {
+        GeoLocation {
+            latitude,
+            longitude,
+        }
+        .normalized()
+    }
+
+    /// Bring the coordinates into their valid ranges.
+    ///
+    /// The longitude is wrapped into `-180.0..180.0`, so `190.0` becomes `-170.0`, and the
+    /// latitude is clamped to `-90.0..=90.0`. Use this on input from sources that wrap
+    /// longitudes incorrectly, as the distance calculations assume valid coordinates.
+    ///
+    /// # Returns
+    ///
+    /// A new `GeoLocation` struct with valid coordinates.
+    pub fn normalized(&self) -> GeoLocation {
+        let longitude = if (-180.0..180.0).contains(&self.longitude) {
+            // wrapping valid longitudes would lose precision in the round trip
+            self.longitude
+        } else {
+            (self.longitude + 180.0).rem_euclid(360.0) - 180.0
+        };
+
+        GeoLocation {
+            latitude: self.latitude.clamp(-90.0, 90.0),
+            longitude,
+        }
+    }
+
+    /// Get the location diametrically opposite this one on the globe.
+    ///
+    /// The latitude is negated and the longitude moved by 180 degrees. Out of range
+    /// coordinates are normalized first, so the longitude is always in `-180.0..180.0`.
+    ///
+    /// # Returns
+    ///
+    /// A new `GeoLocation` struct representing the antipode.
+    pub fn antipode(&self) -> GeoLocation {
+        let location = self.normalized();
+        let longitude = if location.longitude < 0.0 {
+            location.longitude + 180.0
+        } else {
+            location.longitude - 180.0
+        };
+
+        GeoLocation {
+            latitude: -location.latitude,
+            longitude,
+        }
+    }
+
+    /// Round the location to a fixed number of decimal places.
+    ///
+    /// Useful for bucketing nearly identical coordinates, for example as a `HashMap` key.
+    /// Five decimal places is roughly one metre.
+    ///
+    /// # Arguments
+    ///
+    /// * `decimals` - A `u8` representing the number of decimal places to keep.
+    ///
+    /// # Returns
+    ///
+    /// A new `GeoLocation` struct with both coordinates rounded.
+    pub fn quantized(&self, decimals: u8) -> GeoLocation {
+        let factor = 10f64.powi(decimals as i32);
+
+        GeoLocation {
+            latitude: (self.latitude * factor).round() / factor,
+            longitude: (self.longitude * factor).round() / factor,
+        }
+    }
+}
+
+/// `GeoLocation` compares its coordinates with `==`, so it is only a true equivalence
+/// relation when neither coordinate is NaN. Locations containing NaN are never equal to
+/// anything, including themselves, and should not be used as `HashMap` keys.
+impl Eq for GeoLocation {}
+
+/// Hashes the bit patterns of the coordinates, treating `-0.0` as `0.0` so that
+/// locations which compare equal also hash equally.
+impl Hash for GeoLocation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn bits(value: f64) -> u64 {
+            if value == 0.0 {
+                0.0f64.to_bits()
+            } else {
+                value.to_bits()
+            }
+        }
+
+        bits(self.latitude).hash(state);
+        bits(self.longitude).hash(state);
+    }
+}
+
+/// Create a `GeoLocation` from a `(latitude, longitude)` tuple.
+///
+/// Note the ordering: latitude comes first, longitude second.
+impl From<(f64, f64)> for GeoLocation {
+    fn from((latitude, longitude): (f64, f64)) -> Self {
+        GeoLocation {
+            latitude,
+            longitude,
+        }
+    }
+}
+
+/// Convert a `GeoLocation` into a `(latitude, longitude)` tuple.
+///
+/// Note the ordering: latitude comes first, longitude second.
+impl From<GeoLocation> for (f64, f64) {
+    fn from(location: GeoLocation) -> Self {
+        (location.latitude, location.longitude)
+    }
+}
+
+/// Create a `GeoLocation` from a `geo_types::Point`, reading `x` as longitude and `y` as latitude.
+#[cfg(feature = "geo-types")]
+impl From<geo_types::Point<f64>> for GeoLocation {
+    fn from(point: geo_types::Point<f64>) -> Self {
+        GeoLocation::from(point.0)
+    }
+}
+
+/// Create a `GeoLocation` from a `geo_types::Coord`, reading `x` as longitude and `y` as latitude.
+#[cfg(feature = "geo-types")]
+impl From<geo_types::Coord<f64>> for GeoLocation {
+    fn from(coord: geo_types::Coord<f64>) -> Self {
+        GeoLocation {
+            latitude: coord.y,
+            longitude: coord.x,
+        }
+    }
+}
+
+/// Convert a `GeoLocation` into a `geo_types::Point` with `x = longitude` and `y = latitude`.
+#[cfg(feature = "geo-types")]
+impl From<GeoLocation> for geo_types::Point<f64> {
+    fn from(location: GeoLocation) -> Self {
+        location.to_geo_point()
+    }
+}
+
+/// Convert a `GeoLocation` into a `geo_types::Coord` with `x = longitude` and `y = latitude`.
+#[cfg(feature = "geo-types")]
+impl From<GeoLocation> for geo_types::Coord<f64> {
+    fn from(location: GeoLocation) -> Self {
+        geo_types::Coord {
+            x: location.longitude,
+            y: location.latitude,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_tuple() {
+        let location = GeoLocation::from((51.5, 0.1));
+        assert_eq!(location.latitude, 51.5);
+        assert_eq!(location.longitude, 0.1);
+
+        let tuple: (f64, f64) = location.into();
+        assert_eq!(tuple, (51.5, 0.1));
+    }
+
+    #[test]
+    fn test_antipode() {
+        let location = GeoLocation::from((51.5, 0.1));
+        let antipode = location.antipode();
+        assert_eq!(antipode.latitude, -51.5);
+        assert!((antipode.longitude + 179.9).abs() < 1e-9);
+        assert!(antipode.antipode().distance(&location) < 1e-6);
+
+        let half_circumference = std::f64::consts::PI * crate::EARTH_RADIUS;
+        assert!((location.distance(&antipode) - half_circumference).abs() < 1e-6);
+
+        assert_eq!(
+            GeoLocation::from((0.0, 0.0)).antipode(),
+            GeoLocation::from((0.0, -180.0))
+        );
+        assert_eq!(
+            GeoLocation::from((-10.0, 190.0)).antipode(),
+            GeoLocation::from((10.0, 10.0))
+        );
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let location = GeoLocation::from((51.5, 0.1));
+        let bounding_box = location.bounding_box(10.0);
+
+        assert!(crate::haversine::is_within_bounding_box(
+            &location,
+            &bounding_box
+        ));
+        assert!((bounding_box.max_lat - bounding_box.min_lat - 0.18).abs() < 0.001);
+    }
+
+    #[cfg(feature = "geo-types")]
+    #[test]
+    fn test_geo_types() {
+        let location = GeoLocation::from((51.5, 0.1));
+
+        let point = location.to_geo_point();
+        assert_eq!((point.x(), point.y()), (0.1, 51.5));
+        assert_eq!(GeoLocation::from(point), location);
+
+        let coord: geo_types::Coord<f64> = location.clone().into();
+        assert_eq!((coord.x, coord.y), (0.1, 51.5));
+        assert_eq!(GeoLocation::from(coord), location);
+    }
+
+    #[test]
+    fn test_normalized() {
+        assert_eq!(
+            GeoLocation::from((51.5, 190.0)).normalized(),
+            GeoLocation::from((51.5, -170.0))
+        );
+        assert_eq!(
+            GeoLocation::new_normalized(95.0, -200.0),
+            GeoLocation::from((90.0, 160.0))
+        );
+        assert_eq!(
+            GeoLocation::new_normalized(-91.0, 180.0),
+            GeoLocation::from((-90.0, -180.0))
+        );
+        assert_eq!(
+            GeoLocation::from((51.5, 0.1)).normalized(),
+            GeoLocation::from((51.5, 0.1))
+        );
+    }
+
+    #[test]
+    fn test_cardinal_to() {
+        let witham = GeoLocation::from((51.80, 0.64));
+
+        assert_eq!(
+            witham.cardinal_to(&GeoLocation::from((52.80, 0.64))),
+            CompassDirection::North
+        );
+        assert_eq!(
+            witham.cardinal_to(&GeoLocation::from((51.50, -0.14))),
+            CompassDirection::SouthWest
+        );
+    }
+
+    #[test]
+    fn test_hash() {
+        let mut locations = std::collections::HashSet::new();
+        locations.insert(GeoLocation::from((51.5, 0.0)));
+        locations.insert(GeoLocation::from((51.5, -0.0)));
+        locations.insert(GeoLocation::from((51.500001, 0.1)).quantized(4));
+        locations.insert(GeoLocation::from((51.499999, 0.1)).quantized(4));
+
+        assert_eq!(locations.len(), 2);
+        assert!(locations.contains(&GeoLocation::from((51.5, 0.1))));
+    }
 }
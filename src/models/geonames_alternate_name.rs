@@ -0,0 +1,23 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlternateName {
+    // ID of this alternate name
+    pub id: i64,
+    // geonameid referring to the id in the gazetteer
+    pub geoname_id: i64,
+    // iso 639 language code 2- or 3-characters, or a pseudo code such as 'post', 'link' or 'iata'
+    pub iso_language: Option<String>,
+    // alternate name or name variant
+    pub name: String,
+    // if this alternate name is an official/preferred name
+    pub is_preferred_name: bool,
+    // if this is a short name like 'California' for 'State of California'
+    pub is_short_name: bool,
+    // if this alternate name is a colloquial or slang term
+    pub is_colloquial: bool,
+    // if this alternate name is historic and was used in the past
+    pub is_historic: bool,
+    // from period when the name was used
+    pub from: Option<String>,
+    // to period when the name was used
+    pub to: Option<String>,
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use chrono::NaiveDate;
 
 use crate::GeoLocation;
@@ -41,3 +43,14 @@ pub struct Gazetteer {
     // date of last modification
     pub modification_date: NaiveDate,
 }
+
+/// A one line summary such as `Witham (PPL, GB)`.
+impl Display for Gazetteer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}, {})",
+            self.name, self.feature_code, self.country_code
+        )
+    }
+}
@@ -1,9 +1,9 @@
 use std::fmt::Display;
-use std::str::FromStr;
 
-use crate::GeoLocation;
+use crate::{Accuracy, GeoLocation, PostalSummary};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PostalData {
     // iso country code, 2 characters
     pub country_code: String,
@@ -29,44 +29,97 @@ pub struct PostalData {
     pub accuracy: Accuracy,
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub enum Accuracy {
-    NoLocation,
-    NoAccuracyData,
-    Estimated,
-    SamePostalCodeOtherName,
-    GeonameId,
-    Centroid,
+impl PostalData {
+    /// Convert the record into a lightweight `PostalSummary`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the `PostalSummary`, or `None` if the record has no geolocation.
+    pub fn to_summary(&self) -> Option<PostalSummary> {
+        Some(PostalSummary {
+            postal_code: self.postal_code.clone(),
+            location: self.geolocation.clone()?,
+        })
+    }
 }
 
-impl FromStr for Accuracy {
-    type Err = ();
+/// A one line summary such as `CM8 Witham, Essex (Postal code area centroid)`, using the most
+/// specific admin name available and skipping any missing fields.
+impl Display for PostalData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.postal_code)?;
+        if let Some(place_name) = &self.place_name {
+            write!(f, " {}", place_name)?;
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            // higher is better
-            "0" => Ok(Accuracy::NoLocation),
-            "1" => Ok(Accuracy::Estimated),
-            "3" => Ok(Accuracy::SamePostalCodeOtherName),
-            "4" => Ok(Accuracy::GeonameId),
-            "6" => Ok(Accuracy::Centroid),
-            _ => Ok(Accuracy::NoAccuracyData),
+        let admin_name = [&self.admin_name3, &self.admin_name2, &self.admin_name1]
+            .into_iter()
+            .flatten()
+            .find(|name| !name.is_empty());
+        if let Some(admin_name) = admin_name {
+            write!(f, ", {}", admin_name)?;
         }
+
+        write!(f, " ({})", self.accuracy)
     }
 }
 
-impl Display for Accuracy {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Accuracy::Estimated => write!(
-                f,
-                "Estimated as average from numerically neighbouring postal codes"
-            ),
-            Accuracy::SamePostalCodeOtherName => write!(f, "Same postal code, other name"),
-            Accuracy::GeonameId => write!(f, "Place name from geoname id"),
-            Accuracy::Centroid => write!(f, "Postal code area centroid"),
-            Accuracy::NoAccuracyData => write!(f, "No accuracy data"),
-            Accuracy::NoLocation => write!(f, "No location"),
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut postal_data = PostalData {
+            country_code: "GB".to_string(),
+            postal_code: "CM8".to_string(),
+            place_name: Some("Witham".to_string()),
+            admin_name1: Some("England".to_string()),
+            admin_code1: None,
+            admin_name2: Some("Essex".to_string()),
+            admin_code2: None,
+            admin_name3: None,
+            admin_code3: None,
+            geolocation: None,
+            accuracy: Accuracy::Centroid,
+        };
+        assert_eq!(
+            postal_data.to_string(),
+            "CM8 Witham, Essex (Postal code area centroid)"
+        );
+
+        postal_data.place_name = None;
+        postal_data.admin_name2 = None;
+        assert_eq!(
+            postal_data.to_string(),
+            "CM8, England (Postal code area centroid)"
+        );
+    }
+
+    #[test]
+    fn test_to_summary() {
+        let mut postal_data = PostalData {
+            country_code: "GB".to_string(),
+            postal_code: "CM8".to_string(),
+            place_name: Some("Witham".to_string()),
+            admin_name1: None,
+            admin_code1: None,
+            admin_name2: None,
+            admin_code2: None,
+            admin_name3: None,
+            admin_code3: None,
+            geolocation: Some(GeoLocation::from((51.8, 0.63))),
+            accuracy: Accuracy::Centroid,
+        };
+        assert_eq!(
+            postal_data.to_summary(),
+            Some(PostalSummary {
+                postal_code: "CM8".to_string(),
+                location: GeoLocation::from((51.8, 0.63)),
+            })
+        );
+
+        postal_data.geolocation = None;
+        assert_eq!(postal_data.to_summary(), None);
     }
 }
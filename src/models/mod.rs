@@ -1,9 +1,19 @@
-pub use countries::Country;
+pub use accuracy::Accuracy;
+pub use compass_direction::CompassDirection;
+pub use countries::{Country, CountryCode};
+pub use geolocatable::Geolocatable;
 pub use geolocation::GeoLocation;
+pub use geonames_alternate_name::AlternateName;
 pub use geonames_gazetteer::Gazetteer;
-pub use geonames_postal::{Accuracy, PostalData};
+pub use geonames_postal::PostalData;
+pub use postal_summary::PostalSummary;
 
+mod accuracy;
+mod compass_direction;
 mod countries;
+mod geolocatable;
 mod geolocation;
+mod geonames_alternate_name;
 mod geonames_gazetteer;
 mod geonames_postal;
+mod postal_summary;
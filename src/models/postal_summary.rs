@@ -0,0 +1,13 @@
+use crate::GeoLocation;
+
+/// A lightweight postal record holding only the postal code and its location.
+///
+/// Use this instead of `PostalData` when only nearest-postcode lookups are needed, as it
+/// avoids keeping the place and admin names in memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostalSummary {
+    // max 20 character postal code
+    pub postal_code: String,
+    // latitude and longitude in decimal degrees
+    pub location: GeoLocation,
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::{Accuracy, GeoError, GeoLocation, PostalData};
+
+/// Write `PostalData` records to a SQLite database.
+///
+/// The records are written to a `postal` table, replacing any existing one, with an index
+/// on `postal_code`. Geolocated records are also indexed in a `postal_rtree` R*Tree table
+/// keyed by the `postal` row id, so bounding box queries can be answered by SQLite, e.g.
+/// `SELECT p.* FROM postal p JOIN postal_rtree r ON p.id = r.id
+/// WHERE r.min_lat >= ? AND r.max_lat <= ? AND r.min_lon >= ? AND r.max_lon <= ?`.
+///
+/// # Arguments
+///
+/// * `data` - A slice of `PostalData` structs.
+/// * `path` - The path of the database, which is created if it does not exist.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the records were written.
+pub fn export_postal_to_sqlite(
+    data: &[PostalData],
+    path: impl AsRef<Path>,
+) -> Result<(), GeoError> {
+    let mut connection = Connection::open(path)?;
+    let transaction = connection.transaction()?;
+
+    transaction.execute_batch(
+        "DROP TABLE IF EXISTS postal_rtree;
+         DROP TABLE IF EXISTS postal;
+         CREATE TABLE postal (
+             id INTEGER PRIMARY KEY,
+             country_code TEXT NOT NULL,
+             postal_code TEXT NOT NULL,
+             place_name TEXT,
+             admin_name1 TEXT,
+             admin_code1 TEXT,
+             admin_name2 TEXT,
+             admin_code2 TEXT,
+             admin_name3 TEXT,
+             admin_code3 TEXT,
+             latitude REAL,
+             longitude REAL,
+             accuracy INTEGER
+         );
+         CREATE INDEX postal_postal_code ON postal (postal_code);
+         CREATE VIRTUAL TABLE postal_rtree USING rtree (id, min_lat, max_lat, min_lon, max_lon);",
+    )?;
+
+    {
+        let mut insert = transaction.prepare(
+            "INSERT INTO postal VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        )?;
+        let mut insert_rtree =
+            transaction.prepare("INSERT INTO postal_rtree VALUES (?1, ?2, ?2, ?3, ?3)")?;
+
+        for (id, record) in data.iter().enumerate() {
+            let id = id as i64 + 1;
+            let latitude = record
+                .geolocation
+                .as_ref()
+                .map(|location| location.latitude);
+            let longitude = record
+                .geolocation
+                .as_ref()
+                .map(|location| location.longitude);
+            insert.execute(params![
+                id,
+                record.country_code,
+                record.postal_code,
+                record.place_name,
+                record.admin_name1,
+                record.admin_code1,
+                record.admin_name2,
+                record.admin_code2,
+                record.admin_name3,
+                record.admin_code3,
+                latitude,
+                longitude,
+                record.accuracy.code(),
+            ])?;
+
+            if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+                insert_rtree.execute(params![id, latitude, longitude])?;
+            }
+        }
+    }
+
+    transaction.commit()?;
+    log::debug!("Wrote {} postal records to SQLite", data.len());
+
+    Ok(())
+}
+
+/// Read `PostalData` records from a SQLite database written by `export_postal_to_sqlite`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the database.
+///
+/// # Returns
+///
+/// A `Result` containing a `Vec` of `PostalData` structs, in the order they were written.
+pub fn load_postal_from_sqlite(path: impl AsRef<Path>) -> Result<Vec<PostalData>, GeoError> {
+    let connection = Connection::open(path)?;
+    let mut select = connection.prepare(
+        "SELECT country_code, postal_code, place_name, admin_name1, admin_code1, admin_name2,
+                admin_code2, admin_name3, admin_code3, latitude, longitude, accuracy
+         FROM postal ORDER BY id",
+    )?;
+
+    let data = select
+        .query_map([], |row| {
+            let latitude: Option<f64> = row.get(9)?;
+            let longitude: Option<f64> = row.get(10)?;
+            let accuracy: Option<u8> = row.get(11)?;
+
+            Ok(PostalData {
+                country_code: row.get(0)?,
+                postal_code: row.get(1)?,
+                place_name: row.get(2)?,
+                admin_name1: row.get(3)?,
+                admin_code1: row.get(4)?,
+                admin_name2: row.get(5)?,
+                admin_code2: row.get(6)?,
+                admin_name3: row.get(7)?,
+                admin_code3: row.get(8)?,
+                geolocation: latitude.zip(longitude).map(GeoLocation::from),
+                accuracy: match accuracy {
+                    Some(code) => code.to_string().parse().unwrap_or(Accuracy::NoAccuracyData),
+                    None => Accuracy::NoAccuracyData,
+                },
+            })
+        })?
+        .collect::<Result<Vec<PostalData>, rusqlite::Error>>()?;
+
+    log::debug!("Read {} postal records from SQLite", data.len());
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{essex_postal_fixture, temp_path};
+
+    use super::*;
+
+    #[test_log::test]
+    fn test_sqlite_round_trip() {
+        let mut data = essex_postal_fixture();
+        data[2].geolocation = None;
+
+        let path = temp_path("sqlite.db");
+        export_postal_to_sqlite(&data, &path).unwrap();
+        // exporting again replaces the existing table
+        export_postal_to_sqlite(&data, &path).unwrap();
+        assert_eq!(load_postal_from_sqlite(&path).unwrap(), data);
+
+        let connection = Connection::open(&path).unwrap();
+        let postcode: String = connection
+            .query_row(
+                "SELECT p.postal_code FROM postal p JOIN postal_rtree r ON p.id = r.id
+                 WHERE r.min_lat >= 51.75 AND r.max_lat <= 51.85",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(postcode, "CM8");
+
+        drop(connection);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
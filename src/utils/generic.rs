@@ -0,0 +1,491 @@
+use std::cmp::Ordering;
+
+use crate::{haversine, BoundingBox, GeoLocation, Geolocatable};
+
+/// Compare two distances, treating NaN as farther than any other distance.
+///
+/// A malformed coordinate can produce a NaN distance, which must never win a nearest
+/// search or panic a sort.
+pub(crate) fn by_distance(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (false, false) => a.total_cmp(&b),
+        (a_nan, b_nan) => a_nan.cmp(&b_nan),
+    }
+}
+
+/// Sort records paired with their distances, nearest first.
+///
+/// NaN distances are sorted last, and equally distant records keep their original order.
+///
+/// # Arguments
+///
+/// * `records` - A mutable slice of records and their distances, as returned by
+///   `all_by_distance` or `nearest_n_within`.
+pub fn sort_by_distance<T>(records: &mut [(&T, f64)]) {
+    records.sort_by(|(_, a), (_, b)| by_distance(*a, *b));
+}
+
+/// Pair each geolocated record with its distance from a location.
+fn with_distances<T: Geolocatable>(
+    location: GeoLocation,
+    geonames_data: &[T],
+) -> impl Iterator<Item = (&T, f64)> {
+    geonames_data.iter().filter_map(move |geoname| {
+        let distance = geoname.geolocation()?.distance(&location);
+        Some((geoname, distance))
+    })
+}
+
+/// An iterator adapter pairing each geolocated record with its distance from a location.
+///
+/// Created by `GeoDistanceIter::with_distance`.
+#[derive(Debug, Clone)]
+pub struct WithDistance<I> {
+    records: I,
+    location: GeoLocation,
+}
+
+impl<'a, T: Geolocatable + 'a, I: Iterator<Item = &'a T>> Iterator for WithDistance<I> {
+    type Item = (&'a T, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records.by_ref().find_map(|record| {
+            let distance = record.geolocation()?.distance(&self.location);
+            Some((record, distance))
+        })
+    }
+}
+
+/// Extends iterators over records with distance calculations.
+///
+/// This lets distance filters be composed with ordinary iterator adapters, e.g.
+/// `data.iter().with_distance(location).filter(|(_, distance)| *distance < 10.0)`.
+pub trait GeoDistanceIter<'a, T: Geolocatable + 'a>: Iterator<Item = &'a T> + Sized {
+    /// Pair each record with its distance from a location, lazily.
+    ///
+    /// Records without a geolocation are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `Location` struct representing the location.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of records and their distances in kilometers, in their original order.
+    fn with_distance(self, location: GeoLocation) -> WithDistance<Self> {
+        WithDistance {
+            records: self,
+            location,
+        }
+    }
+}
+
+impl<'a, T: Geolocatable + 'a, I: Iterator<Item = &'a T>> GeoDistanceIter<'a, T> for I {}
+
+/// Get the nearest record to a location.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest record.
+pub fn nearest<T: Geolocatable>(location: GeoLocation, geonames_data: &[T]) -> Option<&T> {
+    nearest_matching(location, geonames_data, |_| true)
+}
+
+/// Get the nearest record to a location that matches a predicate.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+/// * `predicate` - A closure returning `true` for records that may be returned.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest matching record.
+pub fn nearest_matching<T: Geolocatable>(
+    location: GeoLocation,
+    geonames_data: &[T],
+    predicate: impl Fn(&T) -> bool,
+) -> Option<&T> {
+    with_distances(location, geonames_data)
+        .filter(|(geoname, _)| predicate(geoname))
+        .min_by(|(_, a), (_, b)| by_distance(*a, *b))
+        .map(|(geoname, _)| geoname)
+}
+
+/// Get the nearest record to a location with a bounding box.
+/// Used to filter out records that are too far away.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+/// * `threshold` - A `f64` representing the maximum distance in kilometers.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest record.
+pub fn nearest_with_bounding<T: Geolocatable>(
+    location: GeoLocation,
+    geonames_data: &[T],
+    threshold: f64,
+) -> Option<&T> {
+    let bounds: BoundingBox = BoundingBox::new(&location, threshold);
+
+    nearest_matching(location, geonames_data, |geoname| {
+        haversine::is_within_bounding_box(geoname.geolocation().unwrap(), &bounds)
+    })
+}
+
+/// Get the nearest record to a location, if it is within a certain radius.
+///
+/// Unlike `nearest_with_bounding`, which can return a record in the corner of the bounding box,
+/// this returns `None` if no record is within `radius` kilometers.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+/// * `radius` - A `f64` representing the radius in kilometers.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest record within the radius.
+pub fn nearest_within<T: Geolocatable>(
+    location: GeoLocation,
+    geonames_data: &[T],
+    radius: f64,
+) -> Option<&T> {
+    nearest_with_bounding(location.clone(), geonames_data, radius)
+        .filter(|geoname| geoname.geolocation().unwrap().is_within(&location, radius))
+}
+
+/// Get the `n` nearest records to a location.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+/// * `n` - A `usize` representing the maximum number of records to return.
+///
+/// # Returns
+///
+/// A `Vec` of references to at most `n` records, sorted by ascending distance.
+pub fn n_nearest<T: Geolocatable>(location: GeoLocation, geonames_data: &[T], n: usize) -> Vec<&T> {
+    n_nearest_matching(location, geonames_data, n, |_| true)
+}
+
+/// Get the `n` nearest records to a location that match a predicate.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+/// * `n` - A `usize` representing the maximum number of records to return.
+/// * `predicate` - A closure returning `true` for records that may be returned.
+///
+/// # Returns
+///
+/// A `Vec` of references to at most `n` matching records, sorted by ascending distance.
+pub fn n_nearest_matching<T: Geolocatable>(
+    location: GeoLocation,
+    geonames_data: &[T],
+    n: usize,
+    predicate: impl Fn(&T) -> bool,
+) -> Vec<&T> {
+    let records = with_distances(location, geonames_data)
+        .filter(|(geoname, _)| predicate(geoname))
+        .collect();
+
+    closest(records, n)
+        .into_iter()
+        .map(|(geoname, _)| geoname)
+        .collect()
+}
+
+/// Get the `n` nearest records to a location that are within a certain radius.
+///
+/// For example, a store locator can ask for up to 5 records but none farther than 25 km.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+/// * `n` - A `usize` representing the maximum number of records to return.
+/// * `radius` - A `f64` representing the radius in kilometers.
+///
+/// # Returns
+///
+/// A `Vec` of at most `n` records within the radius and their distances in kilometers,
+/// sorted by ascending distance.
+pub fn nearest_n_within<T: Geolocatable>(
+    location: GeoLocation,
+    geonames_data: &[T],
+    n: usize,
+    radius: f64,
+) -> Vec<(&T, f64)> {
+    let records = with_distances(location, geonames_data)
+        .filter(|(_, distance)| *distance <= radius)
+        .collect();
+
+    closest(records, n)
+}
+
+/// Keep the `n` closest records, sorted by ascending distance.
+fn closest<T>(mut records: Vec<(&T, f64)>, n: usize) -> Vec<(&T, f64)> {
+    if n == 0 {
+        return Vec::new();
+    }
+    if records.len() > n {
+        // partition around the nth distance so only the closest `n` need sorting
+        records.select_nth_unstable_by(n - 1, |(_, a), (_, b)| by_distance(*a, *b));
+        records.truncate(n);
+    }
+    sort_by_distance(&mut records);
+
+    records
+}
+
+/// Get the record nearest the spherical centroid of a set of records.
+///
+/// This picks a representative record, for example to label a cluster of results from
+/// a radius query. Records without a geolocation are ignored.
+///
+/// # Arguments
+///
+/// * `records` - A slice of records implementing `Geolocatable`.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the most central record, or `None` if no record
+/// has a geolocation or the locations have no defined centroid.
+pub fn most_central<T: Geolocatable>(records: &[T]) -> Option<&T> {
+    let centroid =
+        haversine::spherical_centroid(records.iter().filter_map(|record| record.geolocation()))?;
+
+    nearest(centroid, records)
+}
+
+/// Get every geolocated record paired with its distance from a location.
+///
+/// Records without a geolocation are skipped. Equally distant records keep their
+/// original order.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+///
+/// # Returns
+///
+/// A `Vec` of records and their distances in kilometers, sorted by ascending distance.
+pub fn all_by_distance<T: Geolocatable>(
+    location: GeoLocation,
+    geonames_data: &[T],
+) -> Vec<(&T, f64)> {
+    let mut records: Vec<(&T, f64)> = with_distances(location, geonames_data).collect();
+    sort_by_distance(&mut records);
+
+    records
+}
+
+/// Count the records that have a geolocation.
+///
+/// # Arguments
+///
+/// * `records` - A slice of records implementing `Geolocatable`.
+///
+/// # Returns
+///
+/// A `usize` representing the number of geolocated records.
+pub fn geolocated_count<T: Geolocatable>(records: &[T]) -> usize {
+    records
+        .iter()
+        .filter(|record| record.geolocation().is_some())
+        .count()
+}
+
+/// Get the fraction of records that have a geolocation.
+///
+/// Useful for data quality reporting, e.g. "92% of postal rows have coordinates".
+///
+/// # Arguments
+///
+/// * `records` - A slice of records implementing `Geolocatable`.
+///
+/// # Returns
+///
+/// A `f64` between `0.0` and `1.0`, which is `0.0` if there are no records.
+pub fn geolocated_ratio<T: Geolocatable>(records: &[T]) -> f64 {
+    if records.is_empty() {
+        return 0.0;
+    }
+
+    geolocated_count(records) as f64 / records.len() as f64
+}
+
+/// Get all records within a certain radius of a location.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `radius` - A `f64` representing the radius in kilometers.
+/// * `geonames_data` - A slice of records implementing `Geolocatable`.
+///
+/// # Returns
+///
+/// A `Vec` of references to the records within the radius, in their original order.
+pub fn records_within_radius<T: Geolocatable>(
+    location: GeoLocation,
+    radius: f64,
+    geonames_data: &[T],
+) -> Vec<&T> {
+    let bounds: BoundingBox = BoundingBox::new(&location, radius);
+
+    geonames_data
+        .iter()
+        .filter(|geoname| {
+            geoname.geolocation().is_some_and(|geolocation| {
+                // bounding box can overshoot, so we use it as a first pass
+                haversine::is_within_bounding_box(geolocation, &bounds)
+                    // then we filter out the ones that are still too far away
+                    && geolocation.is_within(&location, radius)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Depot {
+        name: &'static str,
+        location: Option<GeoLocation>,
+    }
+
+    impl Geolocatable for Depot {
+        fn geolocation(&self) -> Option<&GeoLocation> {
+            self.location.as_ref()
+        }
+    }
+
+    fn depot(name: &'static str, latitude: f64, longitude: f64) -> Depot {
+        Depot {
+            name,
+            location: Some(GeoLocation {
+                latitude,
+                longitude,
+            }),
+        }
+    }
+
+    #[test_log::test]
+    fn test_generic_queries_on_custom_type() {
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        let depots = vec![
+            depot("Maldon", 51.73, 0.68),
+            Depot {
+                name: "Unknown",
+                location: None,
+            },
+            depot("Witham", 51.80, 0.64),
+            depot("Chelmsford", 51.73, 0.47),
+        ];
+
+        assert_eq!(nearest(location.clone(), &depots).unwrap().name, "Witham");
+        assert!(nearest_with_bounding(location.clone(), &depots, 0.5).is_none());
+        assert_eq!(
+            nearest_within(location.clone(), &depots, 2.0).unwrap().name,
+            "Witham"
+        );
+
+        let names: Vec<&str> = n_nearest(location.clone(), &depots, 2)
+            .iter()
+            .map(|depot| depot.name)
+            .collect();
+        assert_eq!(names, ["Witham", "Maldon"]);
+
+        let names: Vec<&str> = nearest_n_within(location.clone(), &depots, 5, 10.0)
+            .iter()
+            .map(|(depot, _)| depot.name)
+            .collect();
+        assert_eq!(names, ["Witham", "Maldon"]);
+        assert_eq!(
+            nearest_n_within(location.clone(), &depots, 1, 10.0).len(),
+            1
+        );
+        assert!(nearest_n_within(location.clone(), &depots, 0, 10.0).is_empty());
+
+        assert_eq!(most_central(&depots).unwrap().name, "Witham");
+        assert!(most_central(&depots[1..2]).is_none());
+
+        let distances: Vec<(&str, f64)> = all_by_distance(location.clone(), &depots)
+            .iter()
+            .map(|(depot, distance)| (depot.name, *distance))
+            .collect();
+        assert_eq!(distances.len(), 3);
+        assert_eq!(distances[0].0, "Witham");
+        assert_eq!(distances[2].0, "Chelmsford");
+        assert!(distances.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+        let names: Vec<&str> = records_within_radius(location, 10.0, &depots)
+            .iter()
+            .map(|depot| depot.name)
+            .collect();
+        assert_eq!(names, ["Maldon", "Witham"]);
+
+        assert_eq!(geolocated_count(&depots), 3);
+        assert_eq!(geolocated_ratio(&depots), 0.75);
+        assert_eq!(geolocated_ratio::<Depot>(&[]), 0.0);
+    }
+
+    #[test_log::test]
+    fn test_with_distance() {
+        let location = GeoLocation::from((51.79, 0.63));
+        let depots = [
+            depot("Maldon", 51.73, 0.68),
+            Depot {
+                name: "Unknown",
+                location: None,
+            },
+            depot("Witham", 51.80, 0.64),
+            depot("London", 51.51, -0.13),
+        ];
+
+        let names: Vec<&str> = depots
+            .iter()
+            .with_distance(location.clone())
+            .filter(|(_, distance)| *distance < 10.0)
+            .map(|(depot, _)| depot.name)
+            .collect();
+        assert_eq!(names, ["Maldon", "Witham"]);
+        assert_eq!(depots.iter().with_distance(location).count(), 3);
+    }
+
+    #[test_log::test]
+    fn test_sort_by_distance_with_nan() {
+        let depots = [depot("A", 0.0, 0.0), depot("B", 0.0, 0.0)];
+        let mut records = vec![
+            (&depots[0], f64::NAN),
+            (&depots[1], 2.0),
+            (&depots[0], -f64::NAN),
+            (&depots[1], 1.0),
+        ];
+
+        sort_by_distance(&mut records);
+        assert_eq!(records[0].1, 1.0);
+        assert_eq!(records[1].1, 2.0);
+        assert!(records[2..].iter().all(|(_, distance)| distance.is_nan()));
+        assert_eq!(by_distance(f64::NAN, -f64::NAN), Ordering::Equal);
+        assert_eq!(by_distance(f64::INFINITY, f64::NAN), Ordering::Less);
+    }
+}
@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use crate::haversine::EARTH_RADIUS;
+use crate::utils::by_distance;
+use crate::{Gazetteer, GeoLocation, Geolocatable, PostalData};
+
+/// The default size of a grid cell in degrees.
+pub const DEFAULT_CELL_SIZE: f64 = 0.5;
+
+/// A spatial index that buckets records into fixed latitude/longitude cells.
+///
+/// Radius queries only scan the cells overlapping the search area rather than the
+/// whole dataset, which is much faster on large datasets such as `Country::All`.
+#[derive(Debug, Clone)]
+pub struct GeoGrid<'a, T> {
+    cell_size: f64,
+    lon_cells: i64,
+    cells: HashMap<(i64, i64), Vec<(GeoLocation, &'a T)>>,
+}
+
+impl<'a> GeoGrid<'a, PostalData> {
+    /// Create a new `GeoGrid` from a slice of `PostalData` structs.
+    ///
+    /// Records without a geolocation are not indexed.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_data` - A slice of `PostalData` structs.
+    ///
+    /// # Returns
+    ///
+    /// A `GeoGrid` using cells of `DEFAULT_CELL_SIZE` degrees.
+    pub fn from_postal(geonames_data: &'a [PostalData]) -> Self {
+        Self::from_postal_with_cell_size(geonames_data, DEFAULT_CELL_SIZE)
+    }
+
+    /// Create a new `GeoGrid` from a slice of `PostalData` structs with a custom cell size.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_data` - A slice of `PostalData` structs.
+    /// * `cell_size` - A `f64` representing the size of each cell in degrees.
+    ///
+    /// # Returns
+    ///
+    /// A `GeoGrid` indexing the geolocated records.
+    pub fn from_postal_with_cell_size(geonames_data: &'a [PostalData], cell_size: f64) -> Self {
+        Self::with_cell_size(geonames_data, cell_size)
+    }
+}
+
+impl<'a> GeoGrid<'a, Gazetteer> {
+    /// Create a new `GeoGrid` from a slice of `Gazetteer` structs.
+    ///
+    /// Records without a geolocation are not indexed.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_data` - A slice of `Gazetteer` structs.
+    ///
+    /// # Returns
+    ///
+    /// A `GeoGrid` using cells of `DEFAULT_CELL_SIZE` degrees.
+    pub fn from_gazetteer(geonames_data: &'a [Gazetteer]) -> Self {
+        Self::from_gazetteer_with_cell_size(geonames_data, DEFAULT_CELL_SIZE)
+    }
+
+    /// Create a new `GeoGrid` from a slice of `Gazetteer` structs with a custom cell size.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_data` - A slice of `Gazetteer` structs.
+    /// * `cell_size` - A `f64` representing the size of each cell in degrees.
+    ///
+    /// # Returns
+    ///
+    /// A `GeoGrid` indexing the geolocated records.
+    pub fn from_gazetteer_with_cell_size(geonames_data: &'a [Gazetteer], cell_size: f64) -> Self {
+        Self::with_cell_size(geonames_data, cell_size)
+    }
+}
+
+impl<'a, T: Geolocatable> GeoGrid<'a, T> {
+    /// Create a new `GeoGrid` from a slice of records implementing `Geolocatable`.
+    ///
+    /// Records without a geolocation are not indexed.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_data` - A slice of records implementing `Geolocatable`.
+    ///
+    /// # Returns
+    ///
+    /// A `GeoGrid` using cells of `DEFAULT_CELL_SIZE` degrees.
+    pub fn new(geonames_data: &'a [T]) -> Self {
+        Self::with_cell_size(geonames_data, DEFAULT_CELL_SIZE)
+    }
+
+    /// Create a new `GeoGrid` from a slice of records with a custom cell size.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_data` - A slice of records implementing `Geolocatable`.
+    /// * `cell_size` - A `f64` representing the size of each cell in degrees.
+    ///
+    /// # Returns
+    ///
+    /// A `GeoGrid` indexing the geolocated records.
+    pub fn with_cell_size(geonames_data: &'a [T], cell_size: f64) -> Self {
+        Self::build(
+            geonames_data
+                .iter()
+                .filter_map(|geoname| Some((geoname.geolocation()?.clone(), geoname))),
+            cell_size,
+        )
+    }
+}
+
+impl<'a, T> GeoGrid<'a, T> {
+    fn build(records: impl Iterator<Item = (GeoLocation, &'a T)>, cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "cell size must be positive");
+
+        let mut grid = GeoGrid {
+            cell_size,
+            lon_cells: (360.0 / cell_size).ceil() as i64,
+            cells: HashMap::new(),
+        };
+
+        for (location, record) in records {
+            let cell = (
+                grid.lat_cell(location.latitude),
+                grid.lon_cell(location.longitude),
+            );
+            grid.cells.entry(cell).or_default().push((location, record));
+        }
+
+        log::debug!("Indexed records into {} grid cells", grid.cells.len());
+
+        grid
+    }
+
+    fn lat_cell(&self, latitude: f64) -> i64 {
+        (latitude.clamp(-90.0, 90.0) / self.cell_size).floor() as i64
+    }
+
+    fn lon_cell(&self, longitude: f64) -> i64 {
+        (((longitude + 180.0) / self.cell_size).floor() as i64).rem_euclid(self.lon_cells)
+    }
+
+    /// Get all records within a certain radius of a location.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `GeoLocation` struct representing the location.
+    /// * `radius` - A `f64` representing the radius in kilometers.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of references to the records within the radius, in no particular order.
+    pub fn within_radius(&self, location: GeoLocation, radius: f64) -> Vec<&'a T> {
+        self.located_within_radius(&location, radius)
+            .into_iter()
+            .map(|(_, record)| record)
+            .collect()
+    }
+
+    /// Get the nearest record to a location.
+    ///
+    /// The search radius starts at roughly one cell and doubles until a record is found,
+    /// so only the cells near the location are scanned. Once the radius covers the whole
+    /// globe every record is scanned once instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - A `GeoLocation` struct representing the location.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a reference to the nearest record, or `None` if the grid is empty
+    /// or no record has a finite distance to the location.
+    pub fn nearest(&self, location: GeoLocation) -> Option<&'a T> {
+        if self.cells.is_empty() {
+            return None;
+        }
+
+        let mut radius = self.cell_size * EARTH_RADIUS.to_radians();
+        while radius < PI * EARTH_RADIUS {
+            let nearest = self
+                .located_within_radius(&location, radius)
+                .into_iter()
+                .map(|(record_location, record)| (record_location.distance(&location), record))
+                .min_by(|(a, _), (b, _)| by_distance(*a, *b));
+            if let Some((_, record)) = nearest {
+                return Some(record);
+            }
+
+            radius *= 2.0;
+        }
+
+        // records with NaN coordinates are never within any radius
+        self.cells
+            .values()
+            .flatten()
+            .map(|(record_location, record)| (record_location.distance(&location), *record))
+            .filter(|(distance, _)| distance.is_finite())
+            .min_by(|(a, _), (b, _)| by_distance(*a, *b))
+            .map(|(_, record)| record)
+    }
+
+    fn located_within_radius(
+        &self,
+        location: &GeoLocation,
+        radius: f64,
+    ) -> Vec<(&GeoLocation, &'a T)> {
+        let lat_diff = radius / EARTH_RADIUS.to_radians();
+        let min_lat = (location.latitude - lat_diff).max(-90.0);
+        let max_lat = (location.latitude + lat_diff).min(90.0);
+
+        // the circle is widest in longitude at the latitude furthest from the equator
+        let widest = min_lat.abs().max(max_lat.abs()).to_radians().cos();
+        let lon_diff = radius / (EARTH_RADIUS * widest).to_radians();
+
+        // a circle reaching a pole covers every longitude, and near one `lon_diff` is too
+        // large to convert to a cell index
+        let lon_range: Vec<i64> =
+            if min_lat <= -90.0 || max_lat >= 90.0 || lon_diff.is_nan() || lon_diff >= 180.0 {
+                (0..self.lon_cells).collect()
+            } else {
+                let lon_start = ((location.longitude - lon_diff + 180.0) / self.cell_size).floor();
+                let lon_end = ((location.longitude + lon_diff + 180.0) / self.cell_size).floor();
+                if lon_end - lon_start >= self.lon_cells as f64 {
+                    (0..self.lon_cells).collect()
+                } else {
+                    // wrap around the antimeridian
+                    (lon_start as i64..=lon_end as i64)
+                        .map(|cell| cell.rem_euclid(self.lon_cells))
+                        .collect()
+                }
+            };
+
+        let mut records = Vec::new();
+        for lat_cell in self.lat_cell(min_lat)..=self.lat_cell(max_lat) {
+            for lon_cell in &lon_range {
+                if let Some(cell) = self.cells.get(&(lat_cell, *lon_cell)) {
+                    records.extend(
+                        cell.iter()
+                            .filter(|(record_location, _)| {
+                                record_location.is_within(location, radius)
+                            })
+                            .map(|(record_location, record)| (record_location, *record)),
+                    );
+                }
+            }
+        }
+
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{get_postal_data_within_radius, Accuracy};
+
+    use super::*;
+
+    fn postal_data() -> Vec<PostalData> {
+        // deterministic pseudo-random spread of points across the globe
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        (0..5000)
+            .map(|i| PostalData {
+                country_code: "XX".to_string(),
+                postal_code: i.to_string(),
+                place_name: None,
+                admin_name1: None,
+                admin_code1: None,
+                admin_name2: None,
+                admin_code2: None,
+                admin_name3: None,
+                admin_code3: None,
+                geolocation: Some(GeoLocation {
+                    latitude: next() * 180.0 - 90.0,
+                    longitude: next() * 360.0 - 180.0,
+                }),
+                accuracy: Accuracy::NoAccuracyData,
+            })
+            .collect()
+    }
+
+    #[test_log::test]
+    fn test_within_radius_matches_linear_scan() {
+        let data = postal_data();
+        let grid = GeoGrid::from_postal(&data);
+
+        for (latitude, longitude, radius) in [
+            (51.79, 0.63, 1000.0),
+            (0.0, 179.9, 800.0),
+            (-89.0, 10.0, 500.0),
+            // a circle reaching the pole, where the longitude span is unbounded
+            (80.0, 10.0, 16000.0),
+            (-89.99, 10.0, 20000.0),
+        ] {
+            let location = GeoLocation {
+                latitude,
+                longitude,
+            };
+
+            let mut expected: Vec<&str> = data
+                .iter()
+                .filter(|geoname| {
+                    geoname
+                        .geolocation
+                        .as_ref()
+                        .unwrap()
+                        .is_within(&location, radius)
+                })
+                .map(|geoname| geoname.postal_code.as_str())
+                .collect();
+            let mut found: Vec<&str> = grid
+                .within_radius(location.clone(), radius)
+                .iter()
+                .map(|geoname| geoname.postal_code.as_str())
+                .collect();
+
+            expected.sort();
+            found.sort();
+            assert!(!expected.is_empty());
+            assert_eq!(found, expected);
+        }
+
+        // away from the poles and antimeridian the grid agrees with the existing helper
+        let location = GeoLocation {
+            latitude: 51.79,
+            longitude: 0.63,
+        };
+        assert_eq!(
+            grid.within_radius(location.clone(), 1000.0).len(),
+            get_postal_data_within_radius(location, 1000.0, &data).len()
+        );
+    }
+
+    #[test_log::test]
+    fn test_nearest_matches_linear_scan() {
+        let data = postal_data();
+        let grid = GeoGrid::from_postal(&data);
+
+        for (latitude, longitude) in [(51.79, 0.63), (0.0, 179.99), (-89.9, 10.0), (89.9, -170.0)] {
+            let location = GeoLocation {
+                latitude,
+                longitude,
+            };
+
+            assert_eq!(
+                grid.nearest(location.clone()),
+                crate::get_nearest_postcode(location, &data)
+            );
+        }
+
+        assert!(GeoGrid::from_postal(&[])
+            .nearest(GeoLocation::from((0.0, 0.0)))
+            .is_none());
+    }
+
+    #[test_log::test]
+    fn test_nearest_far_away() {
+        let witham = [GeoLocation::from((51.79, 0.63))];
+        let grid = GeoGrid::new(&witham);
+
+        assert_eq!(
+            grid.nearest(GeoLocation::from((-40.0, 170.0))),
+            Some(&witham[0])
+        );
+        assert_eq!(
+            grid.nearest(GeoLocation::from((-51.79, -179.37))),
+            Some(&witham[0])
+        );
+
+        // records with NaN coordinates never match, but the search still ends
+        let nan = [GeoLocation::from((f64::NAN, f64::NAN))];
+        assert!(GeoGrid::new(&nan)
+            .nearest(GeoLocation::from((0.0, 0.0)))
+            .is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use crate::{GeoLocation, PostalData};
+
+/// An in-memory index of `PostalData` keyed by postal code.
+///
+/// Building the index scans the data once, after which lookups by postal code
+/// are constant time rather than a linear scan of the whole dataset.
+#[derive(Debug, Clone, Default)]
+pub struct PostalLookup {
+    index: HashMap<String, Vec<PostalData>>,
+}
+
+impl PostalLookup {
+    /// Create a new `PostalLookup` from a slice of `PostalData` structs.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_data` - A slice of `PostalData` structs.
+    ///
+    /// # Returns
+    ///
+    /// A `PostalLookup` struct indexing the data by postal code.
+    pub fn new(geonames_data: &[PostalData]) -> Self {
+        let mut index: HashMap<String, Vec<PostalData>> = HashMap::new();
+
+        for geoname in geonames_data {
+            index
+                .entry(geoname.postal_code.clone())
+                .or_default()
+                .push(geoname.clone());
+        }
+
+        log::debug!("Indexed {} postal codes", index.len());
+
+        PostalLookup { index }
+    }
+
+    /// Get postcode data for a given postcode.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A `&str` representing the postcode.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a reference to the first `PostalData` struct for the postcode.
+    pub fn get(&self, code: &str) -> Option<&PostalData> {
+        self.index.get(code).and_then(|rows| rows.first())
+    }
+
+    /// Get all postcode data for a given postcode.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A `&str` representing the postcode.
+    ///
+    /// # Returns
+    ///
+    /// A slice of `PostalData` structs sharing the postcode, empty if the postcode is unknown.
+    pub fn get_all(&self, code: &str) -> &[PostalData] {
+        self.index.get(code).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Get the location of a postcode.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A `&str` representing the postcode.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a `GeoLocation` struct.
+    pub fn location(&self, code: &str) -> Option<GeoLocation> {
+        self.get_all(code)
+            .iter()
+            .find_map(|geoname| geoname.geolocation.as_ref())
+            .cloned()
+    }
+
+    /// Get the number of unique postcodes in the index.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Check if the index contains no postcodes.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Accuracy;
+
+    use super::*;
+
+    fn postal(code: &str, geolocation: Option<GeoLocation>) -> PostalData {
+        PostalData {
+            country_code: "GB".to_string(),
+            postal_code: code.to_string(),
+            place_name: None,
+            admin_name1: None,
+            admin_code1: None,
+            admin_name2: None,
+            admin_code2: None,
+            admin_name3: None,
+            admin_code3: None,
+            geolocation,
+            accuracy: Accuracy::NoAccuracyData,
+        }
+    }
+
+    #[test_log::test]
+    fn test_postal_lookup() {
+        let location = GeoLocation {
+            latitude: 51.8,
+            longitude: 0.63,
+        };
+        let data = vec![
+            postal("CM8", None),
+            postal("CM8", Some(location.clone())),
+            postal("SW1A", None),
+        ];
+
+        let lookup = PostalLookup::new(&data);
+
+        assert_eq!(lookup.len(), 2);
+        assert_eq!(lookup.get_all("CM8").len(), 2);
+        assert_eq!(lookup.get("SW1A").unwrap().postal_code, "SW1A");
+        assert_eq!(lookup.location("CM8"), Some(location));
+        assert_eq!(lookup.location("SW1A"), None);
+        assert!(lookup.get("XX1").is_none());
+    }
+}
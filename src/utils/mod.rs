@@ -1,5 +1,11 @@
+mod generic;
+mod grid;
+mod lookup;
 mod places;
 mod postal;
 
+pub use generic::*;
+pub use grid::*;
+pub use lookup::*;
 pub use places::*;
 pub use postal::*;
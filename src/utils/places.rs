@@ -1,4 +1,10 @@
-use crate::{haversine, BoundingBox, Gazetteer, GeoLocation};
+use std::collections::HashSet;
+
+use crate::utils::{
+    all_by_distance, by_distance, n_nearest_matching, nearest, nearest_matching,
+    nearest_with_bounding, records_within_radius,
+};
+use crate::{haversine, AlternateName, BoundingBox, Gazetteer, GeoLocation};
 
 /// Get the nearest place to a location.
 ///
@@ -11,10 +17,7 @@ use crate::{haversine, BoundingBox, Gazetteer, GeoLocation};
 ///
 /// An `Option` containing a reference to the nearest `Gazetteer` struct.
 pub fn get_nearest_place(location: GeoLocation, geonames_data: &[Gazetteer]) -> Option<&Gazetteer> {
-    geonames_data
-        .iter()
-        .filter(|geoname| geoname.geolocation.is_some())
-        .min_by_key(|geoname| geoname.geolocation.clone().unwrap().distance(&location) as i32)
+    nearest(location, geonames_data)
 }
 
 /// Get the nearest place to a location with a bounding box.
@@ -34,19 +37,239 @@ pub fn get_nearest_place_with_bounding(
     geonames_data: &[Gazetteer],
     threshold: f64,
 ) -> Option<&Gazetteer> {
-    let bounds: BoundingBox = BoundingBox::new(&location, threshold);
+    nearest_with_bounding(location, geonames_data, threshold)
+}
+
+/// Get the nearest place to a location that matches a predicate.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+/// * `predicate` - A closure returning `true` for places that may be returned.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest matching `Gazetteer` struct.
+pub fn get_nearest_place_matching(
+    location: GeoLocation,
+    geonames_data: &[Gazetteer],
+    predicate: impl Fn(&Gazetteer) -> bool,
+) -> Option<&Gazetteer> {
+    nearest_matching(location, geonames_data, predicate)
+}
+
+/// Get the nearest place to a location, excluding the place with a given id.
+///
+/// This is useful for finding the neighbours of a known place, which would otherwise
+/// match itself at a distance of 0. Use `get_nearest_place_matching` to exclude places
+/// by any other criteria.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+/// * `exclude_id` - An `i64` representing the `Gazetteer::id` of the place to exclude.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest other `Gazetteer` struct.
+pub fn get_nearest_place_excluding(
+    location: GeoLocation,
+    geonames_data: &[Gazetteer],
+    exclude_id: i64,
+) -> Option<&Gazetteer> {
+    nearest_matching(location, geonames_data, |geoname| geoname.id != exclude_id)
+}
+
+/// The nearest place to a location, with its distance and direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearestPlace<'a> {
+    pub place: &'a Gazetteer,
+    // great-circle distance from the location in kilometers
+    pub distance_km: f64,
+    // initial bearing from the location to the place in degrees clockwise from north
+    pub bearing_deg: f64,
+}
+
+/// Get the nearest place to a location along with its distance and bearing.
+///
+/// This is useful for describing a location relative to a place, such as "Witham, 2.3 km NE".
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// An `Option` containing a `NearestPlace` struct, or `None` if no place has a geolocation.
+pub fn nearest_place_detailed(
+    location: GeoLocation,
+    geonames_data: &[Gazetteer],
+) -> Option<NearestPlace<'_>> {
+    let place = nearest(location.clone(), geonames_data)?;
+    let place_location = place.geolocation.as_ref()?;
+
+    Some(NearestPlace {
+        place,
+        distance_km: place_location.distance(&location),
+        bearing_deg: haversine::initial_bearing(&location, place_location),
+    })
+}
+
+/// Places within this many kilometers of the nearest populated place are considered
+/// equally close by `get_timezone_at`, which then prefers the most populous of them.
+pub const TIMEZONE_TIE_DISTANCE: f64 = 10.0;
+
+/// Get the timezone at a location.
+///
+/// The timezone is taken from the nearest populated place (feature class `P`).
+/// When several populated places are within `TIMEZONE_TIE_DISTANCE` of the nearest one,
+/// the most populous is used, as a major city's timezone is more reliable than a remote feature's.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the IANA timezone id, e.g. `Europe/London`.
+pub fn get_timezone_at(location: GeoLocation, geonames_data: &[Gazetteer]) -> Option<&str> {
+    let places: Vec<(&Gazetteer, f64)> = geonames_data
+        .iter()
+        .filter(|geoname| geoname.feature_class == "P" && !geoname.timezone.is_empty())
+        .filter_map(|geoname| {
+            let distance = geoname.geolocation.as_ref()?.distance(&location);
+            Some((geoname, distance))
+        })
+        .collect();
+
+    let nearest = places
+        .iter()
+        .map(|(_, distance)| *distance)
+        .min_by(|a, b| by_distance(*a, *b))?;
+
+    places
+        .into_iter()
+        .filter(|(_, distance)| *distance <= nearest + TIMEZONE_TIE_DISTANCE)
+        .max_by_key(|(geoname, _)| geoname.population)
+        .map(|(geoname, _)| geoname.timezone.as_str())
+}
+
+/// Get the most prominent city near a location.
+///
+/// Within the search radius, populated places (feature codes in the `PPL` family) are scored
+/// by population divided by distance, so a large city slightly farther away beats a small
+/// suburb next door. Distances below 0.1 km are treated as 0.1 km.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+/// * `search_radius_km` - A `f64` representing the search radius in kilometers.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the highest scoring `Gazetteer` struct.
+pub fn nearest_city(
+    location: GeoLocation,
+    geonames_data: &[Gazetteer],
+    search_radius_km: f64,
+) -> Option<&Gazetteer> {
+    let bounds: BoundingBox = BoundingBox::new(&location, search_radius_km);
 
     geonames_data
         .iter()
+        .filter(|geoname| geoname.feature_code.starts_with("PPL"))
         .filter(|geoname| geoname.geolocation.is_some())
         .filter(|geoname| {
-            haversine::is_within_bounding_box(&geoname.geolocation.clone().unwrap(), &bounds)
+            haversine::is_within_bounding_box(geoname.geolocation.as_ref().unwrap(), &bounds)
+        })
+        .map(|geoname| {
+            let distance = geoname.geolocation.as_ref().unwrap().distance(&location);
+            (geoname, distance)
         })
-        .min_by_key(|geoname| geoname.geolocation.clone().unwrap().distance(&location) as i32)
+        .filter(|(_, distance)| *distance <= search_radius_km)
+        .max_by(|(a, a_distance), (b, b_distance)| {
+            let a_score = a.population as f64 / a_distance.max(0.1);
+            let b_score = b.population as f64 / b_distance.max(0.1);
+            // on equal scores, prefer the nearer place
+            a_score
+                .total_cmp(&b_score)
+                .then(b_distance.total_cmp(a_distance))
+        })
+        .map(|(geoname, _)| geoname)
+}
+
+/// Get the `n` nearest places to a location.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+/// * `n` - A `usize` representing the maximum number of places to return.
+///
+/// # Returns
+///
+/// A `Vec` of references to at most `n` `Gazetteer` structs, sorted by ascending distance.
+pub fn get_n_nearest_places(
+    location: GeoLocation,
+    geonames_data: &[Gazetteer],
+    n: usize,
+) -> Vec<&Gazetteer> {
+    get_n_nearest_places_matching(location, geonames_data, n, |_| true)
+}
+
+/// Get the `n` nearest places to a location that match a predicate.
+///
+/// For example, pass `|place| place.feature_class == "P"` to get the nearest populated places.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+/// * `n` - A `usize` representing the maximum number of places to return.
+/// * `predicate` - A closure returning `true` for places that may be returned.
+///
+/// # Returns
+///
+/// A `Vec` of references to at most `n` matching `Gazetteer` structs, sorted by ascending distance.
+pub fn get_n_nearest_places_matching(
+    location: GeoLocation,
+    geonames_data: &[Gazetteer],
+    n: usize,
+    predicate: impl Fn(&Gazetteer) -> bool,
+) -> Vec<&Gazetteer> {
+    n_nearest_matching(location, geonames_data, n, predicate)
+}
+
+/// Get every place paired with its distance from a location.
+///
+/// Records without a geolocation are skipped.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&Gazetteer` and their distances in kilometers, sorted by ascending distance.
+pub fn all_places_by_distance(
+    location: GeoLocation,
+    geonames_data: &[Gazetteer],
+) -> Vec<(&Gazetteer, f64)> {
+    all_by_distance(location, geonames_data)
 }
 
 /// Get the location of a place.
 ///
+/// Several places can share a name, so the most populous match with a geolocation is
+/// used. If several of them have the same population, the first in file order is used,
+/// so the result is reproducible. Use `get_place_location_all` to choose between them.
+///
 /// # Arguments
 ///
 /// * `place` - A `&str` representing the place.
@@ -56,21 +279,72 @@ pub fn get_nearest_place_with_bounding(
 ///
 /// An `Option` containing a `Location` struct.
 pub fn get_place_location(place: &str, geonames_data: &[Gazetteer]) -> Option<GeoLocation> {
-    geonames_data
+    get_place_location_all(place, geonames_data)
+        .into_iter()
+        .find_map(|geoname| geoname.geolocation.as_ref())
+        .cloned()
+}
+
+/// Get every place matching a name.
+///
+/// A place matches if its name, ASCII name or one of its alternate names is `place`.
+///
+/// # Arguments
+///
+/// * `place` - A `&str` representing the place.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&Gazetteer` sorted by descending population, with places of equal
+/// population in file order.
+pub fn get_place_location_all<'a>(
+    place: &str,
+    geonames_data: &'a [Gazetteer],
+) -> Vec<&'a Gazetteer> {
+    let mut places: Vec<&Gazetteer> = geonames_data
         .iter()
         .filter(|geoname| {
             geoname.name == place
                 || geoname.asciiname == place
-                || geoname.alternate_names.contains(&place.to_string())
+                || geoname.alternate_names.iter().any(|name| name == place)
         })
-        .filter_map(|geoname| {
-            if geoname.geolocation.is_some() {
-                Some(geoname.geolocation.clone().unwrap())
-            } else {
-                None
-            }
+        .collect();
+    // a stable sort keeps file order between places of equal population
+    places.sort_by_key(|geoname| std::cmp::Reverse(geoname.population));
+
+    places
+}
+
+/// Get a place by one of its localized alternate names.
+///
+/// For example, `"München"` with `Some("de")` finds Munich.
+///
+/// # Arguments
+///
+/// * `name` - A `&str` representing the alternate name.
+/// * `lang` - An optional ISO 639 language code the name must be tagged with.
+/// * `alternate_names` - A slice of `AlternateName` structs.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the matching `Gazetteer` struct.
+pub fn get_place_by_alternate_name<'a>(
+    name: &str,
+    lang: Option<&str>,
+    alternate_names: &[AlternateName],
+    geonames_data: &'a [Gazetteer],
+) -> Option<&'a Gazetteer> {
+    alternate_names
+        .iter()
+        .filter(|alternate_name| alternate_name.name == name)
+        .filter(|alternate_name| lang.is_none() || alternate_name.iso_language.as_deref() == lang)
+        .find_map(|alternate_name| {
+            geonames_data
+                .iter()
+                .find(|geoname| geoname.id == alternate_name.geoname_id)
         })
-        .next()
 }
 
 /// Get all places within a certain radius of a location.
@@ -89,21 +363,109 @@ pub fn get_places_within_radius(
     radius: f64,
     geonames_data: &[Gazetteer],
 ) -> Vec<&str> {
-    let bounds: BoundingBox = BoundingBox::new(&location, radius);
-
-    let places: Vec<&str> = geonames_data
-        .iter()
-        .filter(|geoname| geoname.geolocation.is_some())
-        .filter(|geoname| {
-            // bounding box can overshoot, so we use it as a first pass
-            haversine::is_within_bounding_box(&geoname.geolocation.clone().unwrap(), &bounds)
-        })
-        .filter(|geoname| {
-            // then we filter out the ones that are still too far away
-            geoname.geolocation.clone().unwrap().distance(&location) <= radius
-        })
+    records_within_radius(location, radius, geonames_data)
+        .into_iter()
         .map(|geoname| geoname.name.as_str())
-        .collect();
+        .collect()
+}
+
+/// Get all places within a certain radius of a location with a feature code prefix.
+///
+/// GeoNames feature codes are hierarchical, so a prefix selects a family of places,
+/// e.g. `PPLA` for admin seats, `PPLC` for capitals or `PPL` for any populated place.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `radius` - A `f64` representing the radius in kilometers.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+/// * `code_prefix` - The prefix of the `Gazetteer::feature_code` to match.
+///
+/// # Returns
+///
+/// A `Vec` of references to the matching `Gazetteer` structs, in their original order.
+pub fn places_within_radius_by_feature<'a>(
+    location: GeoLocation,
+    radius: f64,
+    geonames_data: &'a [Gazetteer],
+    code_prefix: &str,
+) -> Vec<&'a Gazetteer> {
+    records_within_radius(location, radius, geonames_data)
+        .into_iter()
+        .filter(|geoname| geoname.feature_code.starts_with(code_prefix))
+        .collect()
+}
+
+/// Get the unique names of all places within a certain radius of a location.
+///
+/// Several gazetteer rows can share a name, so unlike `get_places_within_radius` each name
+/// is only returned once.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `radius` - A `f64` representing the radius in kilometers.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&str` containing the place names, sorted alphabetically.
+pub fn get_unique_places_within_radius(
+    location: GeoLocation,
+    radius: f64,
+    geonames_data: &[Gazetteer],
+) -> Vec<&str> {
+    let mut places = get_places_within_radius(location, radius, geonames_data);
+    places.sort_unstable();
+    places.dedup();
 
     places
 }
+
+/// Get the total population of the populated places within a certain radius of a location.
+///
+/// Only populated places (feature codes starting with `PPL`) are counted, so administrative
+/// areas that also carry a population do not double-count the cities they contain. Rows
+/// sharing a `Gazetteer::id` are only counted once.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `radius` - A `f64` representing the radius in kilometers.
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// An `i64` representing the total population.
+pub fn population_within_radius(
+    location: GeoLocation,
+    radius: f64,
+    geonames_data: &[Gazetteer],
+) -> i64 {
+    let mut seen = HashSet::new();
+    records_within_radius(location, radius, geonames_data)
+        .into_iter()
+        .filter(|geoname| geoname.feature_code.starts_with("PPL"))
+        .filter(|geoname| seen.insert(geoname.id))
+        .map(|geoname| geoname.population)
+        .sum()
+}
+
+/// Get the smallest bounding box containing a set of places.
+///
+/// Records without a geolocation are skipped.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `Gazetteer` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the `BoundingBox`, or `None` if no record has a geolocation.
+pub fn bounding_box_of_gazetteer(geonames_data: &[Gazetteer]) -> Option<BoundingBox> {
+    haversine::bounding_box_of(
+        geonames_data
+            .iter()
+            .filter_map(|geoname| geoname.geolocation.as_ref()),
+    )
+}
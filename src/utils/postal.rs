@@ -1,4 +1,11 @@
-use crate::{haversine, BoundingBox, GeoLocation, PostalData};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::haversine::initial_bearing;
+use crate::utils::{
+    all_by_distance, n_nearest, nearest, nearest_matching, nearest_with_bounding, nearest_within,
+    records_within_radius, GeoGrid,
+};
+use crate::{haversine, BoundingBox, GeoError, GeoLocation, PostalData};
 
 /// Get the nearest postcode to a location.
 ///
@@ -14,10 +21,55 @@ pub fn get_nearest_postcode(
     location: GeoLocation,
     geonames_data: &[PostalData],
 ) -> Option<&PostalData> {
-    geonames_data
+    nearest(location, geonames_data)
+}
+
+/// Get the nearest postcode to each of many locations.
+///
+/// A `GeoGrid` is built once and every location is answered against it, which is much
+/// faster than calling `get_nearest_postcode` in a loop for large batches.
+///
+/// # Arguments
+///
+/// * `points` - A slice of `GeoLocation` structs to reverse geocode.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` containing the nearest `PostalData` struct for each location, in the same order as `points`.
+pub fn reverse_geocode_batch<'a>(
+    points: &[GeoLocation],
+    geonames_data: &'a [PostalData],
+) -> Vec<Option<&'a PostalData>> {
+    let grid = GeoGrid::from_postal(geonames_data);
+
+    points
         .iter()
-        .filter(|geoname| geoname.geolocation.is_some())
-        .min_by_key(|geoname| geoname.geolocation.clone().unwrap().distance(&location) as i32)
+        .map(|point| grid.nearest(point.clone()))
+        .collect()
+}
+
+/// Get the nearest postcode to a location, reporting why none could be found.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Result` containing a reference to the nearest `PostalData` struct, or
+/// `GeoError::EmptyDataset` if the slice is empty, or `GeoError::NoGeolocatedRecords`
+/// if none of the records have a geolocation.
+pub fn try_get_nearest_postcode(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+) -> Result<&PostalData, GeoError> {
+    if geonames_data.is_empty() {
+        return Err(GeoError::EmptyDataset);
+    }
+
+    get_nearest_postcode(location, geonames_data).ok_or(GeoError::NoGeolocatedRecords)
 }
 
 /// Get the nearest postcode to a location with a bounding box.
@@ -37,15 +89,252 @@ pub fn get_nearest_postcode_with_bounding(
     geonames_data: &[PostalData],
     threshold: f64,
 ) -> Option<&PostalData> {
-    let bounds: BoundingBox = BoundingBox::new(&location, threshold);
+    nearest_with_bounding(location, geonames_data, threshold)
+}
 
-    geonames_data
-        .iter()
-        .filter(|geoname| geoname.geolocation.is_some())
-        .filter(|geoname| {
-            haversine::is_within_bounding_box(&geoname.geolocation.clone().unwrap(), &bounds)
-        })
-        .min_by_key(|geoname| geoname.geolocation.clone().unwrap().distance(&location) as i32)
+/// Get the nearest postcode to a location, excluding a given postcode.
+///
+/// Every row with the excluded postcode is skipped, which is useful for suggesting nearby
+/// alternatives to a postcode that is already known.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+/// * `exclude` - A `&str` representing the postcode to exclude.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest `PostalData` struct with another postcode.
+pub fn nearest_postcode_excluding<'a>(
+    location: GeoLocation,
+    geonames_data: &'a [PostalData],
+    exclude: &str,
+) -> Option<&'a PostalData> {
+    nearest_matching(location, geonames_data, |geoname| {
+        geoname.postal_code != exclude
+    })
+}
+
+/// Get the nearest postcode to a location, if it is within a certain radius.
+///
+/// `get_nearest_postcode_with_bounding` can return a postcode in the corner of the bounding box,
+/// farther away than the threshold. This also checks the exact distance, so it returns `None`
+/// if there is no postcode within `radius` kilometers.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+/// * `radius` - A `f64` representing the radius in kilometers.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest `PostalData` struct within the radius.
+pub fn get_nearest_postcode_within(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+    radius: f64,
+) -> Option<&PostalData> {
+    nearest_within(location, geonames_data, radius)
+}
+
+/// Get the nearest postcode to a location out of a shortlist of postcodes.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `codes` - A slice of the postcodes that may be returned.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing a reference to the nearest `PostalData` struct whose postcode is
+/// in `codes`.
+pub fn nearest_among<'a>(
+    location: GeoLocation,
+    codes: &[&str],
+    geonames_data: &'a [PostalData],
+) -> Option<&'a PostalData> {
+    let codes: HashSet<&str> = codes.iter().copied().collect();
+    nearest_matching(location, geonames_data, |geoname| {
+        codes.contains(geoname.postal_code.as_str())
+    })
+}
+
+/// Get every record sharing the nearest postcode to a location.
+///
+/// A postcode often covers several places, each with its own row. Rows are only grouped
+/// with the nearest one if they also share its `country_code`, as different countries can
+/// use the same codes.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing a `Vec` of references to the rows with the nearest postcode,
+/// in their original order, or `None` if no record has a geolocation.
+pub fn nearest_postcode_group(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+) -> Option<Vec<&PostalData>> {
+    let nearest = get_nearest_postcode(location, geonames_data)?;
+
+    Some(
+        geonames_data
+            .iter()
+            .filter(|geoname| {
+                geoname.postal_code == nearest.postal_code
+                    && geoname.country_code == nearest.country_code
+            })
+            .collect(),
+    )
+}
+
+/// Snap a location to the nearest postcode.
+///
+/// Useful for normalizing raw GPS fixes to the representative location of their postcode.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the nearest postcode and its `GeoLocation`.
+pub fn snap_to_postcode(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+) -> Option<(String, GeoLocation)> {
+    let postcode = get_nearest_postcode(location, geonames_data)?;
+
+    Some((
+        postcode.postal_code.clone(),
+        postcode.geolocation.as_ref()?.clone(),
+    ))
+}
+
+/// The administrative divisions containing a location, borrowed from a `PostalData` record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminHierarchy<'a> {
+    // 1. order subdivision (state)
+    pub admin_name1: Option<&'a str>,
+    // 1. order subdivision (state) Code
+    pub admin_code1: Option<&'a str>,
+    // 2. order subdivision (county/province)
+    pub admin_name2: Option<&'a str>,
+    // 2. order subdivision (county/province) Code
+    pub admin_code2: Option<&'a str>,
+    // 3. order subdivision (community)
+    pub admin_name3: Option<&'a str>,
+    // 3. order subdivision (community) Code
+    pub admin_code3: Option<&'a str>,
+}
+
+/// Get the administrative divisions containing a location.
+///
+/// The divisions are taken from the nearest postcode, so they are only as precise as the
+/// postal data around the location.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the `AdminHierarchy` of the nearest postcode.
+pub fn admin_hierarchy_at(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+) -> Option<AdminHierarchy<'_>> {
+    let postcode = get_nearest_postcode(location, geonames_data)?;
+
+    Some(AdminHierarchy {
+        admin_name1: postcode.admin_name1.as_deref(),
+        admin_code1: postcode.admin_code1.as_deref(),
+        admin_name2: postcode.admin_name2.as_deref(),
+        admin_code2: postcode.admin_code2.as_deref(),
+        admin_name3: postcode.admin_name3.as_deref(),
+        admin_code3: postcode.admin_code3.as_deref(),
+    })
+}
+
+/// Guess the country containing a location.
+///
+/// The country is taken from the nearest postcode, so this is only a rough guess near
+/// borders and coastlines, but it needs no country boundaries. Load `Country::All` to cover
+/// every country.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the ISO country code of the nearest postcode.
+pub fn country_at(location: GeoLocation, geonames_data: &[PostalData]) -> Option<&str> {
+    let postcode = get_nearest_postcode(location, geonames_data)?;
+
+    Some(&postcode.country_code)
+}
+
+/// How far, in degrees, the second postcode returned by `nearest_pair` may be from
+/// directly opposite the nearest one.
+pub const NEAREST_PAIR_TOLERANCE: f64 = 45.0;
+
+/// Get the nearest postcode and the nearest postcode on the opposite side of a location.
+///
+/// The second postcode is the nearest one whose bearing from the location is within
+/// `NEAREST_PAIR_TOLERANCE` degrees of the opposite of the first's, so the location lies
+/// roughly between them. Useful for interpolating values between postal centroids.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the nearest `PostalData` struct and the nearest one opposite it.
+pub fn nearest_pair(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+) -> Option<(&PostalData, &PostalData)> {
+    let nearest = get_nearest_postcode(location.clone(), geonames_data)?;
+    let bearing = initial_bearing(&location, nearest.geolocation.as_ref().unwrap());
+
+    let opposite = nearest_matching(location.clone(), geonames_data, |geoname| {
+        let other = initial_bearing(&location, geoname.geolocation.as_ref().unwrap());
+        let difference = (other - bearing).rem_euclid(360.0);
+        (difference - 180.0).abs() <= NEAREST_PAIR_TOLERANCE
+    })?;
+
+    Some((nearest, opposite))
+}
+
+/// Get the `n` nearest postcodes to a location.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+/// * `n` - A `usize` representing the maximum number of postcodes to return.
+///
+/// # Returns
+///
+/// A `Vec` of references to at most `n` `PostalData` structs, sorted by ascending distance.
+pub fn get_n_nearest_postcodes(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+    n: usize,
+) -> Vec<&PostalData> {
+    n_nearest(location, geonames_data, n)
 }
 
 /// Get the location of a postcode.
@@ -62,14 +351,123 @@ pub fn get_postcode_location(postcode: &str, geonames_data: &[PostalData]) -> Op
     geonames_data
         .iter()
         .filter(|geoname| geoname.postal_code == postcode)
-        .filter_map(|geoname| {
-            if geoname.geolocation.is_some() {
-                Some(geoname.geolocation.clone().unwrap())
-            } else {
-                None
-            }
+        .find_map(|geoname| geoname.geolocation.as_ref())
+        .cloned()
+}
+
+/// Get the distance between two postcodes.
+///
+/// Each postcode is resolved with `get_postcode_location`.
+///
+/// # Arguments
+///
+/// * `a` - A `&str` representing the first postcode.
+/// * `b` - A `&str` representing the second postcode.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the distance in kilometers, or `None` if either postcode is
+/// missing or has no geolocation.
+pub fn distance_between_postcodes(a: &str, b: &str, geonames_data: &[PostalData]) -> Option<f64> {
+    let a = get_postcode_location(a, geonames_data)?;
+    let b = get_postcode_location(b, geonames_data)?;
+
+    Some(a.distance(&b))
+}
+
+/// Get all postcodes starting with a prefix.
+///
+/// The prefix is trimmed and matched case-insensitively, so `" cm"` matches `CM8`.
+///
+/// # Arguments
+///
+/// * `prefix` - A `&str` representing the start of the postcode, e.g. `"CM"`.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&str` containing the unique matching postcodes, sorted.
+pub fn postcodes_with_prefix<'a>(prefix: &str, geonames_data: &'a [PostalData]) -> Vec<&'a str> {
+    let prefix = prefix.trim();
+
+    let mut postcodes: Vec<&str> = geonames_data
+        .iter()
+        .map(|geoname| geoname.postal_code.as_str())
+        .filter(|postcode| {
+            postcode
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
         })
-        .next()
+        .collect();
+    postcodes.sort_unstable();
+    postcodes.dedup();
+
+    postcodes
+}
+
+/// Get every record whose postcode starts with a partial postcode.
+///
+/// Unlike `postcodes_with_prefix`, this returns the full records, with their locations, so
+/// the candidate areas for a partial postcode such as a GB outward code can be mapped. The
+/// partial postcode is trimmed and matched case-insensitively.
+///
+/// Matching is a plain prefix match, so `"SW1"` also matches the districts `SW10` to `SW19`
+/// as well as `SW1A` to `SW1Y`.
+///
+/// # Arguments
+///
+/// * `partial` - A `&str` representing the start of the postcode, e.g. `"CM8"`.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of references to the matching `PostalData` structs, sorted by descending
+/// `Accuracy` and then by postcode.
+pub fn expand_partial_postcode<'a>(
+    partial: &str,
+    geonames_data: &'a [PostalData],
+) -> Vec<&'a PostalData> {
+    let partial = partial.trim();
+
+    let mut records: Vec<&PostalData> = geonames_data
+        .iter()
+        .filter(|geoname| {
+            geoname
+                .postal_code
+                .get(..partial.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(partial))
+        })
+        .collect();
+    records.sort_by(|a, b| {
+        b.accuracy
+            .cmp(&a.accuracy)
+            .then_with(|| a.postal_code.cmp(&b.postal_code))
+    });
+
+    records
+}
+
+/// Get the centroid of a postcode.
+///
+/// A postcode covers an area and usually has several rows, so this averages the
+/// geolocations of all of them using a spherical mean.
+///
+/// # Arguments
+///
+/// * `postcode` - A `&str` representing the postcode.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing a `GeoLocation` struct, or `None` if no row for the postcode has a geolocation.
+pub fn postal_code_centroid(postcode: &str, geonames_data: &[PostalData]) -> Option<GeoLocation> {
+    haversine::spherical_centroid(
+        geonames_data
+            .iter()
+            .filter(|geoname| geoname.postal_code == postcode)
+            .filter_map(|geoname| geoname.geolocation.as_ref()),
+    )
 }
 
 /// Get all postcodes within a certain radius of a location.
@@ -88,23 +486,49 @@ pub fn get_postcodes_within_radius(
     radius: f64,
     geonames_data: &[PostalData],
 ) -> Vec<&str> {
-    let bounds: BoundingBox = BoundingBox::new(&location, radius);
+    records_within_radius(location, radius, geonames_data)
+        .into_iter()
+        .map(|geoname| geoname.postal_code.as_str())
+        .collect()
+}
 
-    let postcodes: Vec<&str> = geonames_data
+/// Get all postcodes within the radius of any of several locations.
+///
+/// Each record is checked against every centre in a single pass, so this is cheaper than
+/// calling `get_postcodes_within_radius` once per centre and merging the results.
+///
+/// # Arguments
+///
+/// * `centres` - A slice of `(GeoLocation, f64)` tuples, each a location and its radius in kilometers.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&str` containing each matching postcode once, in the order they first appear in the data.
+pub fn postcodes_within_radii<'a>(
+    centres: &[(GeoLocation, f64)],
+    geonames_data: &'a [PostalData],
+) -> Vec<&'a str> {
+    let bounds: Vec<(&GeoLocation, f64, BoundingBox)> = centres
+        .iter()
+        .map(|(centre, radius)| (centre, *radius, centre.bounding_box(*radius)))
+        .collect();
+    let mut seen = HashSet::new();
+
+    geonames_data
         .iter()
-        .filter(|geoname| geoname.geolocation.is_some())
-        .filter(|geoname| {
-            // bounding box can overshoot, so we use it as a first pass
-            haversine::is_within_bounding_box(&geoname.geolocation.clone().unwrap(), &bounds)
-        })
         .filter(|geoname| {
-            // then we filter out the ones that are still too far away
-            geoname.geolocation.clone().unwrap().distance(&location) <= radius
+            let Some(geolocation) = &geoname.geolocation else {
+                return false;
+            };
+            bounds.iter().any(|(centre, radius, bounds)| {
+                haversine::is_within_bounding_box(geolocation, bounds)
+                    && geolocation.is_within(centre, *radius)
+            })
         })
         .map(|geoname| geoname.postal_code.as_str())
-        .collect();
-
-    postcodes
+        .filter(|postcode| seen.insert(*postcode))
+        .collect()
 }
 
 /// Get all `PostalData` structs within a certain radius of a location.
@@ -123,21 +547,32 @@ pub fn get_postal_data_within_radius(
     radius: f64,
     geonames_data: &[PostalData],
 ) -> Vec<&PostalData> {
-    let bounds: BoundingBox = BoundingBox::new(&location, radius);
-
-    let mut loc: Vec<&PostalData> = geonames_data
-        .iter()
-        .filter(|geoname| geoname.geolocation.is_some())
-        .filter(|geoname| {
-            haversine::is_within_bounding_box(&geoname.geolocation.clone().unwrap(), &bounds)
-        })
-        .filter(|geoname| geoname.geolocation.clone().unwrap().distance(&location) <= radius)
-        .collect();
+    let mut loc: Vec<&PostalData> = records_within_radius(location, radius, geonames_data);
     loc.dedup();
 
     loc
 }
 
+/// Get every postcode paired with its distance from a location.
+///
+/// Unlike the radius functions this returns the whole dataset, which is useful for
+/// analysis. Records without a geolocation are skipped.
+///
+/// # Arguments
+///
+/// * `location` - A `Location` struct representing the location.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&PostalData` and their distances in kilometers, sorted by ascending distance.
+pub fn all_postal_by_distance(
+    location: GeoLocation,
+    geonames_data: &[PostalData],
+) -> Vec<(&PostalData, f64)> {
+    all_by_distance(location, geonames_data)
+}
+
 /// Get postcode data for a given postcode.
 ///
 /// # Arguments
@@ -147,6 +582,7 @@ pub fn get_postal_data_within_radius(
 /// # Returns
 ///
 /// An `Option` containing a `PostalData` struct.
+#[allow(clippy::iter_overeager_cloned)]
 pub fn get_postcode(
     postcode: &str,
     geonames_data: &[PostalData],
@@ -158,3 +594,267 @@ pub fn get_postcode(
         .next()
 }
 
+/// Check whether a postcode exists, without looking up its location.
+///
+/// Surrounding whitespace is ignored and the match is case-insensitive, so `" cm8"`
+/// matches `CM8`.
+///
+/// # Arguments
+///
+/// * `postcode` - A `&str` representing the postcode.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// `true` if any record has the postcode.
+pub fn postcode_exists(postcode: &str, geonames_data: &[PostalData]) -> bool {
+    let postcode = postcode.trim();
+    geonames_data
+        .iter()
+        .any(|geoname| geoname.postal_code.eq_ignore_ascii_case(postcode))
+}
+
+/// Check whether a postcode exists in a given country.
+///
+/// This behaves like `postcode_exists`, but only matches records whose `country_code`
+/// is `country_code`, which is useful when `geonames_data` contains several countries.
+///
+/// # Arguments
+///
+/// * `postcode` - A `&str` representing the postcode.
+/// * `country_code` - A `&str` representing the ISO country code, for example `"GB"`.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// `true` if a record in the country has the postcode.
+pub fn postcode_exists_in(
+    postcode: &str,
+    country_code: &str,
+    geonames_data: &[PostalData],
+) -> bool {
+    let postcode = postcode.trim();
+    let country_code = country_code.trim();
+    geonames_data.iter().any(|geoname| {
+        geoname.country_code.eq_ignore_ascii_case(country_code)
+            && geoname.postal_code.eq_ignore_ascii_case(postcode)
+    })
+}
+
+/// Get all postcodes in a first order subdivision (state/region).
+///
+/// The region is matched case-insensitively against either `admin_name1` or `admin_code1`,
+/// so `"england"` and `"ENG"` both work. Combine this with the radius functions to scope a
+/// search to a jurisdiction. Counties such as Essex are second order subdivisions in GB
+/// data, so use `get_postal_data_in_admin2` for them.
+///
+/// # Arguments
+///
+/// * `admin1` - A `&str` representing the region name or code.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&PostalData` in the region, in their original order.
+pub fn get_postal_data_in_admin1<'a>(
+    admin1: &str,
+    geonames_data: &'a [PostalData],
+) -> Vec<&'a PostalData> {
+    let admin1 = admin1.trim();
+    geonames_data
+        .iter()
+        .filter(|geoname| {
+            [&geoname.admin_name1, &geoname.admin_code1]
+                .into_iter()
+                .flatten()
+                .any(|value| value.eq_ignore_ascii_case(admin1))
+        })
+        .collect()
+}
+
+/// Get all postcodes in a second order subdivision (county/province).
+///
+/// The subdivision is matched case-insensitively against either `admin_name2` or
+/// `admin_code2`, so in GB data `"essex"` and `"11E"` both work.
+///
+/// # Arguments
+///
+/// * `admin2` - A `&str` representing the subdivision name or code.
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of `&PostalData` in the subdivision, in their original order.
+pub fn get_postal_data_in_admin2<'a>(
+    admin2: &str,
+    geonames_data: &'a [PostalData],
+) -> Vec<&'a PostalData> {
+    let admin2 = admin2.trim();
+    geonames_data
+        .iter()
+        .filter(|geoname| {
+            [&geoname.admin_name2, &geoname.admin_code2]
+                .into_iter()
+                .flatten()
+                .any(|value| value.eq_ignore_ascii_case(admin2))
+        })
+        .collect()
+}
+
+/// Get the distinct first order subdivision (state/region) names in a set of postcodes.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of the unique, non-empty `admin_name1` values, sorted alphabetically.
+pub fn distinct_admin1(geonames_data: &[PostalData]) -> Vec<&str> {
+    distinct(geonames_data.iter().map(|geoname| &geoname.admin_name1))
+}
+
+/// Get the distinct second order subdivision (county/province) names in a set of postcodes.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// A `Vec` of the unique, non-empty `admin_name2` values, sorted alphabetically.
+pub fn distinct_admin2(geonames_data: &[PostalData]) -> Vec<&str> {
+    distinct(geonames_data.iter().map(|geoname| &geoname.admin_name2))
+}
+
+fn distinct<'a>(names: impl Iterator<Item = &'a Option<String>>) -> Vec<&'a str> {
+    names
+        .flatten()
+        .map(|name| name.as_str())
+        .filter(|name| !name.trim().is_empty())
+        .collect::<BTreeSet<&str>>()
+        .into_iter()
+        .collect()
+}
+
+/// Find postcodes whose rows are located far apart, which usually indicates a data problem.
+///
+/// Rows are grouped by country and postal code, so the same code in two countries is not
+/// reported. Rows without a geolocation are ignored.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `PostalData` structs.
+/// * `threshold` - A `f64` representing the maximum expected distance in kilometers.
+///
+/// # Returns
+///
+/// A `Vec` of each postcode whose rows are more than `threshold` kilometers apart, with the
+/// largest distance between any two of its rows, in the order the postcodes first appear.
+pub fn find_inconsistent_postcodes(
+    geonames_data: &[PostalData],
+    threshold: f64,
+) -> Vec<(&str, f64)> {
+    let mut order = Vec::new();
+    let mut groups: HashMap<(&str, &str), Vec<&GeoLocation>> = HashMap::new();
+    for geoname in geonames_data {
+        let Some(geolocation) = &geoname.geolocation else {
+            continue;
+        };
+
+        let key = (geoname.country_code.as_str(), geoname.postal_code.as_str());
+        groups
+            .entry(key)
+            .or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            })
+            .push(geolocation);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|key| {
+            let locations = &groups[&key];
+            let max_distance = locations
+                .iter()
+                .enumerate()
+                .flat_map(|(i, a)| locations[i + 1..].iter().map(move |b| a.distance(b)))
+                .fold(0.0, f64::max);
+
+            (max_distance > threshold).then_some((key.1, max_distance))
+        })
+        .collect()
+}
+
+/// Get the smallest bounding box containing a set of postcodes.
+///
+/// Records without a geolocation are skipped.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `PostalData` structs.
+///
+/// # Returns
+///
+/// An `Option` containing the `BoundingBox`, or `None` if no record has a geolocation.
+pub fn bounding_box_of_postal(geonames_data: &[PostalData]) -> Option<BoundingBox> {
+    haversine::bounding_box_of(
+        geonames_data
+            .iter()
+            .filter_map(|geoname| geoname.geolocation.as_ref()),
+    )
+}
+
+/// Count the records in each cell of a latitude/longitude grid over a bounding box.
+///
+/// The box is split into `rows` equal bands of latitude and `cols` equal bands of longitude.
+/// The first row is the northernmost and the first column the westernmost, so the grid can
+/// be drawn directly as a heatmap. Records outside the box or without a geolocation are not
+/// counted, and records on the far edges of the box are counted in the last row or column.
+///
+/// # Arguments
+///
+/// * `geonames_data` - A slice of `PostalData` structs.
+/// * `bounds` - A `BoundingBox` struct representing the region to cover.
+/// * `cols` - A `usize` representing the number of columns.
+/// * `rows` - A `usize` representing the number of rows.
+///
+/// # Returns
+///
+/// A `Vec` of `rows` rows, each a `Vec` of `cols` record counts.
+pub fn density_grid(
+    geonames_data: &[PostalData],
+    bounds: &BoundingBox,
+    cols: usize,
+    rows: usize,
+) -> Vec<Vec<u32>> {
+    let mut grid = vec![vec![0; cols]; rows];
+    if cols == 0 || rows == 0 {
+        return grid;
+    }
+
+    let lat_span = bounds.max_lat - bounds.min_lat;
+    let lon_span = bounds.max_lon - bounds.min_lon;
+    let cell = |offset: f64, span: f64, cells: usize| {
+        // NaN from an empty span casts to 0
+        ((offset / span * cells as f64) as usize).min(cells - 1)
+    };
+
+    for geolocation in geonames_data
+        .iter()
+        .filter_map(|geoname| geoname.geolocation.as_ref())
+    {
+        if !haversine::is_within_bounding_box(geolocation, bounds) {
+            continue;
+        }
+
+        // measured eastwards so a box spanning the antimeridian counts from its western edge
+        let lon_offset = (geolocation.longitude - bounds.min_lon).rem_euclid(360.0);
+        let row = cell(bounds.max_lat - geolocation.latitude, lat_span, rows);
+        let col = cell(lon_offset, lon_span, cols);
+        grid[row][col] += 1;
+    }
+
+    grid
+}